@@ -1,44 +1,156 @@
 use anyhow::Result;
 use std::io::Write;
-use whis_core::{ApiConfig, Settings};
-
-pub fn ensure_ffmpeg_installed() -> Result<()> {
-    if std::process::Command::new("ffmpeg")
-        .arg("-version")
-        .output()
-        .is_err()
-    {
-        eprintln!("Error: FFmpeg is not installed or not in PATH.");
-        eprintln!("\nwhis requires FFmpeg for audio compression.");
-        eprintln!("Please install FFmpeg:");
-        eprintln!("  - Ubuntu/Debian: sudo apt install ffmpeg");
-        eprintln!("  - macOS: brew install ffmpeg");
-        eprintln!("  - Windows: choco install ffmpeg or download from ffmpeg.org");
-        eprintln!("  - Or visit: https://ffmpeg.org/download.html\n");
-        std::process::exit(1);
-    }
-    Ok(())
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use whis_core::{
+    AudioRecorder, AutoStop, InputLevel, Progress, ProgressCallback, Settings,
+    TranscriptionOptions, TranscriptionProvider, WhisError,
+};
+
+use crate::args::TranscriptionArgs;
+
+/// Width of the level meter in characters
+const METER_WIDTH: usize = 20;
+/// Input level at which the meter is empty
+const METER_FLOOR_DB: f32 = -60.0;
+
+/// Load the configured transcription provider (local model or API)
+pub fn load_provider() -> Result<Arc<dyn TranscriptionProvider>> {
+    Ok(whis_core::provider::from_settings(&Settings::load())?)
 }
 
-pub fn load_api_config() -> Result<ApiConfig> {
-    // Priority: settings file > environment variable
-    let settings = Settings::load();
-    if let Some(key) = settings.openai_api_key {
-        return Ok(ApiConfig {
-            openai_api_key: key,
-        });
-    }
+/// Audio recorder for the input device saved in settings (or the source in
+/// `WHIS_AUDIO_SOURCE`)
+pub fn new_recorder() -> Result<AudioRecorder> {
+    let mut recorder = AudioRecorder::new()?;
+    recorder.set_source(whis_core::source::from_settings(&Settings::load())?);
+    Ok(recorder)
+}
 
-    // Fallback to environment
-    match ApiConfig::from_env() {
-        Ok(cfg) => Ok(cfg),
-        Err(_) => {
-            eprintln!("Error: No API key configured.");
-            eprintln!("\nSet your key with:");
-            eprintln!("  whis config --api-key YOUR_KEY\n");
-            eprintln!("Or set the OPENAI_API_KEY environment variable.");
-            std::process::exit(1);
+/// Recovery hint for errors the user can fix themselves
+pub fn error_hint(err: &anyhow::Error) -> Option<String> {
+    whis_error_hint(err.downcast_ref::<WhisError>()?)
+}
+
+fn whis_error_hint(err: &WhisError) -> Option<String> {
+    let hint = match err {
+        WhisError::NoInputDevice => {
+            "Connect a microphone and check that it is enabled in your system sound settings.\n\
+             List available devices with: whis devices\n\
+             Without a microphone, record from a file or test tone instead:\n  \
+             WHIS_AUDIO_SOURCE=file:speech.wav (or WHIS_AUDIO_SOURCE=tone)"
+                .to_string()
+        }
+        WhisError::EmptyRecording => {
+            "No audio was captured. Check that the right microphone is selected and not muted."
+                .to_string()
         }
+        WhisError::FfmpegMissing => "The ffmpeg encoder is selected in settings.\n\
+             Switch to the built-in encoder: whis config --encoder mp3\n\
+             Or install FFmpeg:\n  \
+             - Ubuntu/Debian: sudo apt install ffmpeg\n  \
+             - macOS: brew install ffmpeg\n  \
+             - Windows: choco install ffmpeg or download from ffmpeg.org\n  \
+             - Or visit: https://ffmpeg.org/download.html"
+            .to_string(),
+        WhisError::MissingApiKey => "Set your key with:\n  \
+             whis config --api-key YOUR_KEY\n\
+             Or set the OPENAI_API_KEY environment variable.\n\n\
+             For a self-hosted server, set its URL instead:\n  \
+             whis config --base-url http://localhost:8000/v1"
+            .to_string(),
+        WhisError::Authentication { .. } => "Check the saved key with: whis config --show\n\
+             Set a new one with: whis config --api-key YOUR_KEY"
+            .to_string(),
+        WhisError::RateLimited { .. } => {
+            "Wait a moment and try again, or check your plan and usage limits.".to_string()
+        }
+        WhisError::QuotaExceeded { .. } => "Your account has run out of credits.\n\
+             Check billing at: https://platform.openai.com/settings/organization/billing"
+            .to_string(),
+        WhisError::Timeout { .. } | WhisError::Network { .. } => {
+            "Check your internet connection, or that your self-hosted server is running."
+                .to_string()
+        }
+        WhisError::ModelNotDownloaded(model) => {
+            format!("Download it with: whis model pull {model}")
+        }
+        WhisError::LocalWhisperUnavailable(_) => {
+            "Reinstall with: cargo install whis --features local-whisper\n\
+             Or switch back to the API: whis model use --remote"
+                .to_string()
+        }
+        WhisError::Decoding(_) => "Supported are WAV, MP3, FLAC, Ogg Vorbis, and AAC or ALAC in \
+             MP4, M4A or MKV.\n\
+             Convert other files first, e.g.: ffmpeg -i input.wma output.flac"
+            .to_string(),
+        WhisError::ClipboardUnavailable(_) => {
+            "On Wayland, make sure wl-clipboard is installed.".to_string()
+        }
+        WhisError::RecordingSaved { source, dir } => {
+            let later = format!(
+                "Transcribe the saved recording once that is fixed:\n  \
+                 whis transcribe {}/* -o {}",
+                dir.display(),
+                dir.display()
+            );
+            match whis_error_hint(source) {
+                Some(hint) => format!("{hint}\n{later}"),
+                None => later,
+            }
+        }
+        _ => return None,
+    };
+    Some(hint)
+}
+
+/// Retry notice for the status line, e.g. "Chunk 2 failed (...), retrying in 1.5s"
+pub fn retry_notice(progress: &Progress) -> Option<String> {
+    let Progress::Retry {
+        chunk,
+        error,
+        delay,
+        ..
+    } = progress
+    else {
+        return None;
+    };
+    let what = match chunk {
+        Some(chunk) => format!("Chunk {chunk}"),
+        None => "Transcription".to_string(),
+    };
+    Some(format!(
+        "{what} failed ({error}), retrying in {:.1}s...",
+        delay.as_secs_f64()
+    ))
+}
+
+/// Progress callback that prints retries to stderr, each on its own line
+pub fn print_retries() -> ProgressCallback {
+    Box::new(|progress| {
+        if let Some(notice) = retry_notice(&progress) {
+            eprintln!("\r\x1b[K{notice}");
+        }
+    })
+}
+
+/// Print an error with its recovery hint
+pub fn report_error(err: &anyhow::Error) {
+    eprintln!("Error: {err}");
+    if let Some(hint) = error_hint(err) {
+        eprintln!("\n{hint}");
+    }
+}
+
+/// Transcription options from settings, with command-line flags taking priority
+pub fn transcription_options(overrides: TranscriptionArgs) -> TranscriptionOptions {
+    let saved = TranscriptionOptions::from_settings(&Settings::load());
+    TranscriptionOptions {
+        model: overrides.model.or(saved.model),
+        language: overrides.language.or(saved.language),
+        prompt: overrides.prompt.or(saved.prompt),
+        temperature: overrides.temperature.or(saved.temperature),
     }
 }
 
@@ -48,3 +160,66 @@ pub fn wait_for_enter() -> Result<()> {
     std::io::stdin().read_line(&mut input)?;
     Ok(())
 }
+
+/// How to end a recording, for the "Recording..." prompt
+pub fn stop_hint(auto_stop: Option<AutoStop>) -> String {
+    match auto_stop {
+        Some(auto_stop) => format!(
+            "press Enter to stop, or pause for {}s",
+            auto_stop.timeout.as_secs_f32()
+        ),
+        None => "press Enter to stop".to_string(),
+    }
+}
+
+/// Elapsed time and a level meter, e.g. "0:07 [#######   |        ]".
+/// The bar shows the RMS level, the `|` marks the peak.
+pub fn level_meter(level: &InputLevel) -> String {
+    let width = |db: f32| {
+        let fraction = ((db - METER_FLOOR_DB) / -METER_FLOOR_DB).clamp(0.0, 1.0);
+        (fraction * METER_WIDTH as f32).round() as usize
+    };
+    let (rms, peak) = (width(level.rms_db), width(level.peak_db));
+    let bar: String = (0..METER_WIDTH)
+        .map(|i| match i {
+            i if i < rms => '#',
+            i if i + 1 == peak => '|',
+            _ => ' ',
+        })
+        .collect();
+    let secs = level.elapsed_secs as u64;
+    format!("{}:{:02} [{bar}]", secs / 60, secs % 60)
+}
+
+/// Wait for Enter, or until `silence` fires when auto-stop is on
+pub fn wait_for_enter_or_silence(silence: Option<oneshot::Receiver<()>>) -> Result<()> {
+    let Some(mut silence) = silence else {
+        return wait_for_enter();
+    };
+    std::io::stdout().flush()?;
+
+    // Reading stdin blocks, so do it on a thread; it is left behind if silence wins
+    let (enter_tx, enter_rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let mut input = String::new();
+        let _ = std::io::stdin().read_line(&mut input);
+        let _ = enter_tx.send(());
+    });
+
+    loop {
+        if enter_rx.recv_timeout(Duration::from_millis(50)).is_ok() {
+            return Ok(());
+        }
+        match silence.try_recv() {
+            Ok(()) => {
+                println!();
+                return Ok(());
+            }
+            Err(oneshot::error::TryRecvError::Empty) => {}
+            Err(oneshot::error::TryRecvError::Closed) => {
+                enter_rx.recv()?;
+                return Ok(());
+            }
+        }
+    }
+}
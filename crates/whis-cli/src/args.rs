@@ -1,4 +1,9 @@
-use clap::{Parser, Subcommand};
+use clap::builder::BoolishValueParser;
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
+use whis_core::{
+    AudioEncoder, EncodingProfile, MAX_PRE_ROLL_MS, OutputFormat, PcmFormat, VadAggressiveness,
+};
 
 #[derive(Parser)]
 #[command(name = "whis")]
@@ -8,6 +13,70 @@ use clap::{Parser, Subcommand};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<Commands>,
+
+    #[command(flatten)]
+    pub transcription: TranscriptionArgs,
+
+    /// Also write the transcript to a file (.txt, .srt, .vtt or .json)
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Transcribe while recording and show the text live (OpenAI Realtime API)
+    #[arg(long)]
+    pub stream: bool,
+}
+
+/// Per-run overrides for the transcription options saved with `whis config`
+#[derive(Args, Clone, Default)]
+pub struct TranscriptionArgs {
+    /// Transcription model for this run (e.g. "gpt-4o-transcribe")
+    #[arg(long)]
+    pub model: Option<String>,
+
+    /// Spoken language as ISO-639-1 code (e.g. "en")
+    #[arg(long)]
+    pub language: Option<String>,
+
+    /// Prompt to guide spelling of names and jargon
+    #[arg(long)]
+    pub prompt: Option<String>,
+
+    /// Sampling temperature between 0 and 1
+    #[arg(long, value_parser = parse_temperature)]
+    pub temperature: Option<f32>,
+}
+
+/// Parse a sampling temperature, rejecting values outside 0..=1
+pub fn parse_temperature(value: &str) -> Result<f32, String> {
+    let temperature: f32 = value
+        .parse()
+        .map_err(|_| format!("'{value}' is not a number"))?;
+    if !(0.0..=1.0).contains(&temperature) {
+        return Err("temperature must be between 0 and 1".to_string());
+    }
+    Ok(temperature)
+}
+
+/// Parse an auto-stop timeout in seconds (0 turns it off)
+fn parse_auto_stop(value: &str) -> Result<f32, String> {
+    let secs: f32 = value
+        .parse()
+        .map_err(|_| format!("'{value}' is not a number"))?;
+    if !secs.is_finite() || secs < 0.0 {
+        return Err("timeout must be 0 or more seconds".to_string());
+    }
+    Ok(secs)
+}
+
+/// Parse a pre-roll length in milliseconds (0 turns the warm mic off)
+fn parse_pre_roll(value: &str) -> Result<u32, String> {
+    let ms: u32 = value
+        .parse()
+        .map_err(|_| format!("'{value}' is not a whole number of milliseconds"))?;
+    if ms > MAX_PRE_ROLL_MS {
+        return Err(format!("pre-roll can be at most {MAX_PRE_ROLL_MS} ms"));
+    }
+    Ok(ms)
 }
 
 #[derive(Subcommand)]
@@ -17,22 +86,187 @@ pub enum Commands {
         /// Hotkey to trigger recording (e.g., "ctrl+shift+r")
         #[arg(short = 'k', long, default_value = "ctrl+shift+r")]
         hotkey: String,
+
+        /// Hotkey to cancel a transcription in progress
+        #[arg(long, default_value = "ctrl+shift+x")]
+        cancel_hotkey: String,
+
+        /// Hotkey to discard the recording in progress without transcribing
+        #[arg(long, default_value = "ctrl+shift+d")]
+        discard_hotkey: String,
+
+        /// Hotkey to pause the recording in progress, or resume it
+        #[arg(long, default_value = "ctrl+shift+p")]
+        pause_hotkey: String,
+
+        #[command(flatten)]
+        transcription: TranscriptionArgs,
     },
 
     /// Stop the background service
     Stop,
 
+    /// Cancel the transcription in progress in the background service
+    Cancel,
+
+    /// Discard the recording in progress in the background service (no API call)
+    Discard,
+
+    /// Pause the recording in progress in the background service
+    Pause,
+
+    /// Resume the paused recording in the background service
+    Resume,
+
     /// Check service status
     Status,
 
+    /// Transcribe existing audio or video files (wav, mp3, m4a, ogg, flac, mp4, ...)
+    Transcribe(TranscribeArgs),
+
+    /// List audio input devices
+    Devices,
+
     /// Configure settings (API key, etc.)
-    Config {
-        /// Set your OpenAI API key
-        #[arg(long)]
-        api_key: Option<String>,
-
-        /// Show current configuration
-        #[arg(long)]
-        show: bool,
+    Config(ConfigArgs),
+    /// Manage local Whisper models for offline transcription
+    Model {
+        #[command(subcommand)]
+        action: ModelAction,
+    },
+}
+
+/// Files to transcribe with `whis transcribe`
+#[derive(Args)]
+pub struct TranscribeArgs {
+    /// Audio or video files to transcribe; `-` reads audio piped on stdin
+    #[arg(required = true, value_name = "FILE")]
+    pub files: Vec<PathBuf>,
+
+    /// Write the transcript to this file instead of printing it; with several files,
+    /// a directory that gets one transcript per file
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+
+    /// Transcript format: txt, srt, vtt or json (default: from the output file
+    /// extension, otherwise txt)
+    #[arg(long)]
+    pub format: Option<OutputFormat>,
+
+    /// Transcribe every file again instead of skipping the ones an earlier run of the
+    /// same batch finished
+    #[arg(long)]
+    pub no_resume: bool,
+
+    /// Stdin is headerless PCM at this sample rate (e.g. 16000); without it, stdin must
+    /// be a container such as WAV, MP3 or Ogg
+    #[arg(long, value_name = "HZ", value_parser = clap::value_parser!(u32).range(1..))]
+    pub rate: Option<u32>,
+
+    /// Channels of headerless PCM on stdin
+    #[arg(long, default_value_t = 1, requires = "rate", value_parser = clap::value_parser!(u16).range(1..))]
+    pub channels: u16,
+
+    /// Sample format of headerless PCM on stdin: s16le, s32le or f32le
+    #[arg(long, default_value_t = PcmFormat::S16Le, requires = "rate")]
+    pub sample_format: PcmFormat,
+
+    #[command(flatten)]
+    pub transcription: TranscriptionArgs,
+}
+
+/// Settings to change with `whis config`
+#[derive(Args)]
+pub struct ConfigArgs {
+    /// Set your OpenAI API key
+    #[arg(long)]
+    pub api_key: Option<String>,
+
+    /// Use an OpenAI-compatible server (e.g. "http://localhost:8000/v1"); empty to reset
+    #[arg(long)]
+    pub base_url: Option<String>,
+
+    /// Transcription model name (e.g. "whisper-1"); empty to reset
+    #[arg(long)]
+    pub model: Option<String>,
+
+    /// Spoken language as ISO-639-1 code (e.g. "en"); empty for auto-detect
+    #[arg(long)]
+    pub language: Option<String>,
+
+    /// Prompt to guide spelling of names and jargon; empty to reset
+    #[arg(long)]
+    pub prompt: Option<String>,
+
+    /// Sampling temperature between 0 and 1; empty to reset
+    #[arg(long)]
+    pub temperature: Option<String>,
+
+    /// Input device to record from (see `whis devices`); empty for the system default
+    #[arg(long)]
+    pub input_device: Option<String>,
+
+    /// Audio encoder for uploads: mp3, flac or ffmpeg (needs FFmpeg installed)
+    #[arg(long)]
+    pub encoder: Option<AudioEncoder>,
+
+    /// Upload quality: speech (16 kHz mono), balanced (24 kHz mono) or original
+    #[arg(long)]
+    pub encoding_profile: Option<EncodingProfile>,
+
+    /// Trim silence before transcription: off (default), low, medium or high
+    #[arg(long)]
+    pub vad: Option<VadAggressiveness>,
+
+    /// Stop recording after this many seconds of silence following speech; 0 to turn off
+    #[arg(long, value_name = "SECS", value_parser = parse_auto_stop)]
+    pub auto_stop: Option<f32>,
+
+    /// Level in dBFS below which input counts as silence for auto-stop (e.g. -40)
+    #[arg(long, value_name = "DB", allow_hyphen_values = true)]
+    pub silence_threshold: Option<f32>,
+
+    /// Suppress steady background noise (fans, chatter) before transcription
+    #[arg(long, value_name = "on|off", value_parser = BoolishValueParser::new())]
+    pub denoise: Option<bool>,
+
+    /// Filter out rumble and hum below 80 Hz before transcription
+    #[arg(long, value_name = "on|off", value_parser = BoolishValueParser::new())]
+    pub high_pass: Option<bool>,
+
+    /// Normalize loudness so quiet speakers are transcribed as well as loud ones
+    #[arg(long, value_name = "on|off", value_parser = BoolishValueParser::new())]
+    pub normalize: Option<bool>,
+
+    /// Warm mic for `whis listen`: keep the microphone open and add this much audio from
+    /// before the hotkey press to each recording (e.g. 500); 0 to turn off
+    #[arg(long, value_name = "MS", value_parser = parse_pre_roll)]
+    pub pre_roll: Option<u32>,
+
+    /// Show current configuration
+    #[arg(long)]
+    pub show: bool,
+}
+
+#[derive(Subcommand)]
+pub enum ModelAction {
+    /// List available models and which are downloaded
+    List,
+
+    /// Download a model
+    Pull {
+        /// Model name (e.g. "base.en")
+        name: String,
+    },
+
+    /// Transcribe locally with a downloaded model
+    Use {
+        /// Model name or path to a GGML .bin file
+        #[arg(required_unless_present = "remote")]
+        name: Option<String>,
+
+        /// Switch back to the remote transcription API
+        #[arg(long, conflicts_with = "name")]
+        remote: bool,
     },
 }
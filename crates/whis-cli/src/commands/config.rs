@@ -1,18 +1,135 @@
 use anyhow::Result;
 use whis_core::Settings;
 
-pub fn run(api_key: Option<String>, show: bool) -> Result<()> {
-    if let Some(key) = api_key {
-        // Validate format
-        if !key.starts_with("sk-") {
-            eprintln!("Invalid key format. OpenAI keys start with 'sk-'");
-            std::process::exit(1);
-        }
+use crate::args::ConfigArgs;
+
+/// Treat an empty value as "unset"
+fn non_empty(value: String) -> Option<String> {
+    let value = value.trim().to_string();
+    if value.is_empty() { None } else { Some(value) }
+}
 
+pub fn run(args: ConfigArgs) -> Result<()> {
+    let ConfigArgs {
+        api_key,
+        base_url,
+        model,
+        language,
+        prompt,
+        temperature,
+        input_device,
+        encoder,
+        encoding_profile,
+        vad,
+        auto_stop,
+        silence_threshold,
+        denoise,
+        high_pass,
+        normalize,
+        pre_roll,
+        show,
+    } = args;
+
+    if api_key.is_some()
+        || base_url.is_some()
+        || model.is_some()
+        || language.is_some()
+        || prompt.is_some()
+        || temperature.is_some()
+        || input_device.is_some()
+        || encoder.is_some()
+        || encoding_profile.is_some()
+        || vad.is_some()
+        || auto_stop.is_some()
+        || silence_threshold.is_some()
+        || denoise.is_some()
+        || high_pass.is_some()
+        || normalize.is_some()
+        || pre_roll.is_some()
+    {
         let mut settings = Settings::load();
-        settings.openai_api_key = Some(key);
+
+        if let Some(url) = base_url {
+            settings.base_url = non_empty(url);
+        }
+
+        if let Some(model) = model {
+            settings.model = non_empty(model);
+        }
+
+        if let Some(language) = language {
+            settings.language = non_empty(language);
+        }
+
+        if let Some(prompt) = prompt {
+            settings.prompt = non_empty(prompt);
+        }
+
+        if let Some(temperature) = temperature {
+            settings.temperature = match non_empty(temperature) {
+                Some(value) => match crate::args::parse_temperature(&value) {
+                    Ok(temperature) => Some(temperature),
+                    Err(e) => {
+                        eprintln!("Invalid temperature: {e}");
+                        std::process::exit(1);
+                    }
+                },
+                None => None,
+            };
+        }
+
+        if let Some(device) = input_device {
+            settings.input_device = non_empty(device);
+        }
+
+        if let Some(encoder) = encoder {
+            settings.encoder = encoder;
+        }
+
+        if let Some(profile) = encoding_profile {
+            settings.encoding_profile = profile;
+        }
+
+        if let Some(vad) = vad {
+            settings.vad = vad;
+        }
+
+        if let Some(secs) = auto_stop {
+            settings.auto_stop_secs = (secs > 0.0).then_some(secs);
+        }
+
+        if let Some(threshold) = silence_threshold {
+            settings.silence_threshold_db = threshold;
+        }
+
+        if let Some(denoise) = denoise {
+            settings.denoise = denoise;
+        }
+
+        if let Some(high_pass) = high_pass {
+            settings.high_pass = high_pass;
+        }
+
+        if let Some(normalize) = normalize {
+            settings.normalize = normalize;
+        }
+
+        if let Some(ms) = pre_roll {
+            settings.pre_roll_ms = (ms > 0).then_some(ms);
+        }
+
+        if let Some(key) = api_key {
+            // Validate format (self-hosted servers accept arbitrary keys)
+            if settings.base_url.is_none() && !key.starts_with("sk-") {
+                eprintln!("Invalid key format. OpenAI keys start with 'sk-'");
+                eprintln!("Using a self-hosted server? Set --base-url as well.");
+                std::process::exit(1);
+            }
+            settings.openai_api_key = non_empty(key);
+        }
+
         settings.save()?;
-        println!("API key saved to {}", Settings::path().display());
+        println!("Settings saved to {}", Settings::path().display());
         return Ok(());
     }
 
@@ -27,14 +144,77 @@ pub fn run(api_key: Option<String>, show: bool) -> Result<()> {
                 "***".to_string()
             };
             println!("API key: {masked}");
+        } else if settings.base_url.is_some() {
+            // The environment key is only sent to OpenAI, never to a custom server
+            println!("API key: (not set)");
         } else {
             println!("API key: (not set, using $OPENAI_API_KEY)");
         }
+        match &settings.base_url {
+            Some(url) => println!("Base URL: {url}"),
+            None => println!("Base URL: (OpenAI)"),
+        }
+        match &settings.model {
+            Some(model) => println!("Model: {model}"),
+            None => println!("Model: (default)"),
+        }
+        match &settings.language {
+            Some(language) => println!("Language: {language}"),
+            None => println!("Language: (auto-detect)"),
+        }
+        if let Some(prompt) = &settings.prompt {
+            println!("Prompt: {prompt}");
+        }
+        if let Some(temperature) = settings.temperature {
+            println!("Temperature: {temperature}");
+        }
+        match &settings.input_device {
+            Some(device) => println!("Input device: {device}"),
+            None => println!("Input device: (system default)"),
+        }
+        println!("Encoder: {}", settings.encoder);
+        println!("Encoding profile: {}", settings.encoding_profile);
+        println!("Silence trimming: {}", settings.vad);
+        match settings.auto_stop_secs {
+            Some(secs) => println!(
+                "Auto-stop: after {secs}s below {} dBFS",
+                settings.silence_threshold_db
+            ),
+            None => println!("Auto-stop: off"),
+        }
+        let stages: Vec<&str> = [
+            (settings.denoise, "denoise"),
+            (settings.high_pass, "high-pass"),
+            (settings.normalize, "normalize"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, name)| enabled.then_some(name))
+        .collect();
+        if stages.is_empty() {
+            println!("Audio clean-up: off");
+        } else {
+            println!("Audio clean-up: {}", stages.join(", "));
+        }
+        match settings.pre_roll() {
+            Some(pre_roll) => println!(
+                "Warm mic: on, keeps {} ms before each recording (microphone stays open)",
+                pre_roll.as_millis()
+            ),
+            None => println!("Warm mic: off"),
+        }
         return Ok(());
     }
 
     // No flags - show help
     eprintln!("Usage: whis config --api-key <KEY>");
+    eprintln!("       whis config --base-url <URL> [--model <MODEL>] [--api-key <KEY>]");
+    eprintln!("       whis config --language <CODE> [--prompt <TEXT>] [--temperature <0-1>]");
+    eprintln!("       whis config --input-device <NAME> [--encoder <mp3|flac|ffmpeg>]");
+    eprintln!("       whis config --encoding-profile <speech|balanced|original>");
+    eprintln!("       whis config --vad <off|low|medium|high>");
+    eprintln!("       whis config --auto-stop <SECS> [--silence-threshold <DB>]");
+    eprintln!("       whis config --denoise <on|off> --high-pass <on|off> --normalize <on|off>");
+    eprintln!("       whis config --pre-roll <MS>");
     eprintln!("       whis config --show");
     std::process::exit(1);
 }
@@ -0,0 +1,30 @@
+use anyhow::Result;
+use whis_core::{Settings, list_input_devices};
+
+pub fn run() -> Result<()> {
+    let devices = list_input_devices()?;
+    if devices.is_empty() {
+        println!("No input devices found");
+        return Ok(());
+    }
+
+    let selected = Settings::load().input_device;
+    for device in &devices {
+        let marker = if selected.as_deref() == Some(device.name.as_str()) {
+            "*"
+        } else {
+            " "
+        };
+        let default = if device.is_default { " (default)" } else { "" };
+        println!("{marker} {}{default}", device.name);
+    }
+
+    match selected {
+        Some(name) if !devices.iter().any(|d| d.name == name) => {
+            println!("\nSelected device '{name}' is not connected, using the default device");
+        }
+        Some(_) => {}
+        None => println!("\nSelect one with: whis config --input-device <NAME>"),
+    }
+    Ok(())
+}
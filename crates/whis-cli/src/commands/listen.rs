@@ -1,4 +1,6 @@
 use anyhow::Result;
+use whis_core::{EncodingOptions, Settings};
+use crate::args::TranscriptionArgs;
 use crate::{app, hotkey, ipc, service};
 
 /// Guard to clean up PID and socket files on exit
@@ -10,10 +12,13 @@ impl Drop for CleanupGuard {
     }
 }
 
-pub fn run(hotkey_str: String) -> Result<()> {
-    // Check if FFmpeg is available
-    app::ensure_ffmpeg_installed()?;
-
+pub fn run(
+    hotkey_str: String,
+    cancel_hotkey_str: String,
+    discard_hotkey_str: String,
+    pause_hotkey_str: String,
+    overrides: TranscriptionArgs,
+) -> Result<()> {
     // Check if service is already running
     if ipc::is_service_running() {
         eprintln!("Error: whis service is already running.");
@@ -21,8 +26,15 @@ pub fn run(hotkey_str: String) -> Result<()> {
         std::process::exit(1);
     }
 
-    // Load API configuration
-    let config = app::load_api_config()?;
+    // Load transcription provider (local model or API)
+    let provider = app::load_provider()?;
+    let options = app::transcription_options(overrides);
+
+    // Check that the encoder can run (local models read raw PCM and skip encoding)
+    let encoding = EncodingOptions::from_settings(&Settings::load());
+    if !provider.accepts_pcm() {
+        encoding.encoder.ensure_available()?;
+    }
 
     // Write PID file
     ipc::write_pid_file()?;
@@ -32,15 +44,23 @@ pub fn run(hotkey_str: String) -> Result<()> {
 
     // Setup hotkey listener
     // This handles platform differences internally
-    println!("Registering hotkey: {}", hotkey_str);
-    let (hotkey_rx, _guard) = hotkey::setup(&hotkey_str)?;
+    println!(
+        "Registering hotkey: {hotkey_str} (cancel: {cancel_hotkey_str}, discard: {discard_hotkey_str}, \
+         pause: {pause_hotkey_str})"
+    );
+    let (hotkey_rx, _guard) = hotkey::setup(&[
+        (&hotkey_str, hotkey::HotkeyAction::Toggle),
+        (&cancel_hotkey_str, hotkey::HotkeyAction::Cancel),
+        (&discard_hotkey_str, hotkey::HotkeyAction::Discard),
+        (&pause_hotkey_str, hotkey::HotkeyAction::Pause),
+    ])?;
 
     // Create Tokio runtime
     let runtime = tokio::runtime::Runtime::new()?;
     
     runtime.block_on(async {
         // Create service
-        let service = service::Service::new(config)?;
+        let service = service::Service::new(provider, options, encoding)?;
         
         // Run service loop
         tokio::select! {
@@ -1,5 +1,12 @@
+pub mod cancel;
 pub mod config;
+pub mod devices;
+pub mod discard;
 pub mod listen;
+pub mod model;
+pub mod pause;
 pub mod record_once;
+pub mod resume;
 pub mod status;
 pub mod stop;
+pub mod transcribe;
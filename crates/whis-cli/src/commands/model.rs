@@ -0,0 +1,119 @@
+use anyhow::Result;
+use std::io::{self, Write};
+use whis_core::Settings;
+use whis_core::model::{self, MODELS};
+
+use crate::args::ModelAction;
+
+pub fn run(action: ModelAction) -> Result<()> {
+    match action {
+        ModelAction::List => list(),
+        ModelAction::Pull { name } => pull(&name),
+        ModelAction::Use { name, remote } => use_model(name, remote),
+    }
+}
+
+fn list() -> Result<()> {
+    let settings = Settings::load();
+    let active = settings.local_model.as_deref();
+
+    println!(
+        "Models directory: {}",
+        model::models_dir(&settings).display()
+    );
+    println!();
+    for m in MODELS {
+        let marker = if active == Some(m.name) { "*" } else { " " };
+        let status = if model::is_downloaded(&settings, m.name) {
+            "downloaded"
+        } else {
+            ""
+        };
+        println!(
+            "{marker} {:<16} {:>6} MB  {:<38} {status}",
+            m.name, m.size_mb, m.description
+        );
+    }
+
+    if let Some(active) = active
+        && model::find_model(active).is_none()
+    {
+        println!();
+        println!("* {active} (custom path)");
+    }
+
+    if !model::local_transcription_available() {
+        println!();
+        println!("Note: this build has no local transcription support.");
+        println!("Reinstall with: cargo install whis --features local-whisper");
+    }
+
+    Ok(())
+}
+
+fn pull(name: &str) -> Result<()> {
+    if model::find_model(name).is_none() {
+        eprintln!("Unknown model: {name}");
+        eprintln!("Run 'whis model list' to see available models.");
+        std::process::exit(1);
+    }
+
+    let settings = Settings::load();
+    if model::is_downloaded(&settings, name) {
+        println!("Model '{name}' is already downloaded");
+        return Ok(());
+    }
+
+    let path = model::download_model(&settings, name, |done, total| {
+        match total {
+            Some(total) if total > 0 => {
+                print!("\rDownloading {name}... {}%", done * 100 / total);
+            }
+            _ => print!("\rDownloading {name}... {} MB", done / (1024 * 1024)),
+        }
+        let _ = io::stdout().flush();
+    })?;
+
+    println!("\rDownloaded {name} to {}", path.display());
+    println!("Use it with: whis model use {name}");
+    Ok(())
+}
+
+fn use_model(name: Option<String>, remote: bool) -> Result<()> {
+    let mut settings = Settings::load();
+
+    if remote {
+        settings.local_model = None;
+        settings.save()?;
+        println!("Switched to the remote transcription API");
+        return Ok(());
+    }
+
+    let Some(name) = name else {
+        eprintln!("Usage: whis model use <NAME|PATH>");
+        eprintln!("       whis model use --remote");
+        std::process::exit(1);
+    };
+
+    if !model::is_downloaded(&settings, &name) {
+        eprintln!(
+            "Model '{name}' not found at {}",
+            model::model_path(&settings, &name).display()
+        );
+        if model::find_model(&name).is_some() {
+            eprintln!("Download it with: whis model pull {name}");
+        }
+        std::process::exit(1);
+    }
+
+    settings.local_model = Some(name.clone());
+    settings.save()?;
+    println!("Using local model '{name}' (offline transcription)");
+
+    if !model::local_transcription_available() {
+        println!("Note: this build has no local transcription support.");
+        println!("Reinstall with: cargo install whis --features local-whisper");
+    }
+
+    Ok(())
+}
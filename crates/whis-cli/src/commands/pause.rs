@@ -0,0 +1,11 @@
+use anyhow::Result;
+use crate::ipc;
+
+pub fn run() -> Result<()> {
+    let mut client = ipc::IpcClient::connect()?;
+    match client.send_message(ipc::IpcMessage::Pause)? {
+        ipc::IpcResponse::Error(e) => anyhow::bail!(e),
+        _ => println!("Recording paused (resume with: whis resume)"),
+    }
+    Ok(())
+}
@@ -1,68 +1,150 @@
 use anyhow::Result;
 use std::io::{self, Write};
+use std::path::PathBuf;
+use tokio::sync::mpsc;
 use whis_core::{
-    AudioRecorder, RecordingOutput, copy_to_clipboard, parallel_transcribe,
-    transcribe_audio,
+    ApiConfig, AutoStop, CancellationToken, EncodingOptions, RealtimeConfig, RealtimeEvent,
+    Settings, Transcript, TranscriptionOptions, copy_to_clipboard, stream_transcription,
+    transcribe_while_recording,
 };
 use crate::app;
+use crate::args::TranscriptionArgs;
 
-pub fn run() -> Result<()> {
+pub fn run(overrides: TranscriptionArgs, output: Option<PathBuf>, stream: bool) -> Result<()> {
     // Create Tokio runtime for async operations
     let runtime = tokio::runtime::Runtime::new()?;
 
-    // Check if FFmpeg is available
-    app::ensure_ffmpeg_installed()?;
+    // Saved transcription options, overridden by command-line flags
+    let options = app::transcription_options(overrides);
 
-    // Load API configuration
-    let config = app::load_api_config()?;
+    let transcript = if stream {
+        record_streaming(&runtime, &options)?
+    } else {
+        record_and_transcribe(&runtime, &options)?
+    };
+
+    // Save subtitles/JSON/text if requested
+    if let Some(path) = output {
+        transcript.write_to(&path)?;
+        println!("Saved to {}", path.display());
+    }
+
+    // Copy to clipboard
+    copy_to_clipboard(&transcript.text)?;
+
+    println!("Copied to clipboard");
+
+    Ok(())
+}
+
+/// Record until Enter (or auto-stop); long recordings are uploaded chunk by chunk while recording
+fn record_and_transcribe(
+    runtime: &tokio::runtime::Runtime,
+    options: &TranscriptionOptions,
+) -> Result<Transcript> {
+    // Load transcription provider (local model or API)
+    let provider = app::load_provider()?;
+
+    // Check that the encoder can run (local models read raw PCM and skip encoding)
+    let encoding = EncodingOptions::from_settings(&Settings::load());
+    if !provider.accepts_pcm() {
+        encoding.encoder.ensure_available()?;
+    }
 
     // Create recorder and start recording
-    let mut recorder = AudioRecorder::new()?;
+    let mut recorder = app::new_recorder()?;
+    let audio = recorder.stream_samples();
+    let mut levels = recorder.watch_levels();
+    let auto_stop = AutoStop::from_settings(&Settings::load());
+    let silence = auto_stop.map(|auto_stop| recorder.watch_silence(auto_stop));
     recorder.start_recording()?;
 
-    print!("Recording... (press Enter to stop)");
+    // Completed chunks are encoded and transcribed in the background
+    let (sample_rate, channels) = (recorder.sample_rate(), recorder.channels());
+    let local = provider.accepts_pcm();
+    let options = options.clone();
+    let session = runtime.spawn(async move {
+        // Ctrl+C ends the process, so nothing else needs to cancel here
+        let cancel = CancellationToken::new();
+        transcribe_while_recording(
+            provider,
+            audio,
+            sample_rate,
+            channels,
+            &options,
+            encoding,
+            Some(app::print_retries()),
+            &cancel,
+        )
+        .await
+    });
+
+    // Show elapsed time and input level, so a muted microphone is noticed right away
+    let hint = app::stop_hint(auto_stop);
+    print!("Recording... ({hint})");
     io::stdout().flush()?;
-    app::wait_for_enter()?;
-
-    // Finalize recording and get output
-    let audio_result = recorder.finalize_recording()?;
-
-    // Transcribe based on output type
-    let transcription = match audio_result {
-        RecordingOutput::Single(audio_data) => {
-            // Small file - simple transcription
-            print!("\rTranscribing...                        \n");
-            io::stdout().flush()?;
-
-            match transcribe_audio(&config.openai_api_key, audio_data) {
-                Ok(text) => text,
-                Err(e) => {
-                    eprintln!("Transcription error: {e}");
-                    std::process::exit(1);
-                }
-            }
+    let meter = runtime.spawn(async move {
+        while let Some(level) = levels.recv().await {
+            print!("\r\x1b[KRecording {} ({hint})", app::level_meter(&level));
+            let _ = io::stdout().flush();
         }
-        RecordingOutput::Chunked(chunks) => {
-            // Large file - parallel transcription
-            print!("\rTranscribing...                        \n");
-            io::stdout().flush()?;
-
-            runtime.block_on(async {
-                match parallel_transcribe(&config.openai_api_key, chunks, None).await {
-                    Ok(text) => text,
-                    Err(e) => {
-                        eprintln!("Transcription error: {e}");
-                        std::process::exit(1);
-                    }
-                }
-            })
+    });
+    app::wait_for_enter_or_silence(silence)?;
+
+    // Stopping closes the sample stream, which sends off the last chunk
+    recorder.stop_stream();
+    runtime.block_on(meter)?;
+
+    // Local models transcribe raw samples on the CPU
+    if local {
+        print!("\r\x1b[KTranscribing locally...\n");
+    } else {
+        print!("\r\x1b[KTranscribing...\n");
+    }
+    io::stdout().flush()?;
+
+    Ok(runtime.block_on(session)??)
+}
+
+/// Record until Enter (or auto-stop) while streaming audio to the realtime API, printing text as it arrives
+fn record_streaming(
+    runtime: &tokio::runtime::Runtime,
+    options: &TranscriptionOptions,
+) -> Result<Transcript> {
+    let config = RealtimeConfig::new(&ApiConfig::from_settings(&Settings::load())?, options);
+
+    let mut recorder = app::new_recorder()?;
+    // The realtime API keeps the transcript; no copy of the audio is needed here
+    let audio = recorder.stream_samples();
+    let auto_stop = AutoStop::from_settings(&Settings::load());
+    let silence = auto_stop.map(|auto_stop| recorder.watch_silence(auto_stop));
+    recorder.start_recording()?;
+
+    let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+    let (sample_rate, channels) = (recorder.sample_rate(), recorder.channels());
+    let session = runtime.spawn(async move {
+        stream_transcription(&config, audio, sample_rate, channels, events_tx).await
+    });
+
+    // Rewrite the current line with partial text; finished segments get their own line
+    let printer = runtime.spawn(async move {
+        while let Some(event) = events_rx.recv().await {
+            match event {
+                RealtimeEvent::Partial { text, .. } => print!("\r\x1b[K{text}"),
+                RealtimeEvent::Final { text, .. } => println!("\r\x1b[K{}", text.trim()),
+            }
+            let _ = io::stdout().flush();
         }
-    };
+    });
 
-    // Copy to clipboard
-    copy_to_clipboard(&transcription)?;
+    println!("Recording... ({})", app::stop_hint(auto_stop));
+    app::wait_for_enter_or_silence(silence)?;
 
-    println!("Copied to clipboard");
+    // Stopping closes the sample stream, which commits the remaining audio
+    recorder.stop_stream();
+    println!("Finishing transcription...");
 
-    Ok(())
+    let transcript = runtime.block_on(session)??;
+    runtime.block_on(printer)?;
+    Ok(transcript)
 }
@@ -13,7 +13,9 @@ pub fn run() -> Result<()> {
 
     match response {
         ipc::IpcResponse::Idle => println!("Status: Running (idle)"),
+        ipc::IpcResponse::Warm => println!("Status: Running (idle, warm mic: microphone open)"),
         ipc::IpcResponse::Recording => println!("Status: Running (recording)"),
+        ipc::IpcResponse::Paused => println!("Status: Running (recording paused)"),
         ipc::IpcResponse::Transcribing => println!("Status: Running (transcribing)"),
         ipc::IpcResponse::Error(e) => {
             eprintln!("Error: {e}");
@@ -0,0 +1,379 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use tokio::io::AsyncReadExt;
+use whis_core::{
+    CancellationToken, EncodingOptions, OutputFormat, PcmFormat, Progress, ProgressCallback,
+    RecordingData, Settings, Transcript, TranscriptionOptions, TranscriptionProvider, decode_bytes,
+    decode_file, decode_raw, transcribe_recording,
+};
+
+use crate::app;
+use crate::args::TranscribeArgs;
+
+/// Resume manifest kept in the output directory of a batch
+const MANIFEST_NAME: &str = ".whis-manifest.json";
+
+/// File argument that stands for stdin
+const STDIN: &str = "-";
+/// Bytes read from stdin at a time
+const STDIN_READ_BYTES: usize = 64 * 1024;
+
+pub fn run(args: TranscribeArgs) -> Result<()> {
+    let reads_stdin = args.files.iter().any(|file| file.as_os_str() == STDIN);
+    if reads_stdin {
+        if args.files.len() > 1 {
+            anyhow::bail!("Stdin (-) can't be transcribed together with other files");
+        }
+        if args.output.as_deref().is_some_and(Path::is_dir) {
+            anyhow::bail!("Transcribing stdin needs an output file, not a directory");
+        }
+        if io::stdin().is_terminal() {
+            anyhow::bail!(
+                "No audio piped on stdin (e.g. arecord -f S16_LE -r 16000 -t raw | whis transcribe - --rate 16000)"
+            );
+        }
+    } else if args.rate.is_some() {
+        anyhow::bail!("--rate only applies to audio piped on stdin (-)");
+    }
+    let raw = args.rate.map(|sample_rate| RawPcm {
+        format: args.sample_format,
+        sample_rate,
+        channels: args.channels,
+    });
+
+    let provider = app::load_provider()?;
+    let options = app::transcription_options(args.transcription);
+
+    // Check that the encoder can run (local models read raw PCM and skip encoding)
+    let encoding = EncodingOptions::from_settings(&Settings::load());
+    if !provider.accepts_pcm() {
+        encoding.encoder.ensure_available()?;
+    }
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    let job = Job {
+        runtime: &runtime,
+        provider,
+        options,
+        encoding,
+        raw,
+    };
+
+    let batch = args.files.len() > 1 || args.output.as_deref().is_some_and(Path::is_dir);
+    if !batch {
+        return transcribe_one(&job, &args.files[0], args.output.as_deref(), args.format);
+    }
+    let Some(dir) = args.output else {
+        anyhow::bail!("Transcribing several files needs an output directory: --output DIR");
+    };
+    transcribe_batch(
+        &job,
+        &args.files,
+        &dir,
+        args.format.unwrap_or(OutputFormat::Text),
+        !args.no_resume,
+    )
+}
+
+/// Everything needed to transcribe a file
+struct Job<'a> {
+    runtime: &'a tokio::runtime::Runtime,
+    provider: Arc<dyn TranscriptionProvider>,
+    options: TranscriptionOptions,
+    encoding: EncodingOptions,
+    /// Layout of stdin when it is headerless PCM
+    raw: Option<RawPcm>,
+}
+
+/// Layout of headerless PCM, which can't be detected from the data
+struct RawPcm {
+    format: PcmFormat,
+    sample_rate: u32,
+    channels: u16,
+}
+
+impl Job<'_> {
+    /// Decode `path` (or stdin for `-`) and transcribe it, reporting chunk progress
+    /// after `label`
+    fn transcribe(&self, path: &Path, label: &str) -> Result<Transcript> {
+        let cancel = CancellationToken::new();
+        let recording = if path.as_os_str() == STDIN {
+            let recording = self.read_stdin()?;
+            // Reading stdin took over Ctrl+C; it now cancels the transcription
+            let cancel = cancel.clone();
+            self.runtime.spawn(async move {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    cancel.cancel();
+                }
+            });
+            recording
+        } else {
+            decode_file(path)?
+        };
+
+        let label = label.to_string();
+        let progress: ProgressCallback = Box::new(move |progress| {
+            if let Some(notice) = app::retry_notice(&progress) {
+                eprintln!("\r\x1b[K{notice}");
+                eprint!("{label}");
+            } else if let Progress::Chunk { completed, total } = progress
+                && total > 1
+            {
+                eprint!("\r\x1b[K{label} chunk {completed}/{total}");
+            }
+            let _ = io::stderr().flush();
+        });
+        let transcript = self.runtime.block_on(transcribe_recording(
+            self.provider.clone(),
+            recording,
+            &self.options,
+            self.encoding,
+            Some(progress),
+            &cancel,
+        ))?;
+        Ok(transcript)
+    }
+
+    /// Read stdin until it ends or Ctrl+C is pressed, then decode it. Ctrl+C also
+    /// stops the recorder feeding the pipe, so it ends the audio like EOF would.
+    fn read_stdin(&self) -> Result<RecordingData> {
+        let data = self.runtime.block_on(async {
+            let mut stdin = tokio::io::stdin();
+            let mut data = Vec::new();
+            let interrupted = tokio::signal::ctrl_c();
+            tokio::pin!(interrupted);
+            loop {
+                data.reserve(STDIN_READ_BYTES);
+                tokio::select! {
+                    read = stdin.read_buf(&mut data) => {
+                        if read.context("Failed to read stdin")? == 0 {
+                            break;
+                        }
+                    }
+                    _ = &mut interrupted => break,
+                }
+            }
+            anyhow::Ok(data)
+        })?;
+        if data.is_empty() {
+            anyhow::bail!("Nothing was piped on stdin");
+        }
+        let recording = match &self.raw {
+            Some(raw) => decode_raw(&data, raw.format, raw.sample_rate, raw.channels)?,
+            None => decode_bytes(data)?,
+        };
+        Ok(recording)
+    }
+}
+
+/// Transcribe a single file to stdout, or to `output` if given
+fn transcribe_one(
+    job: &Job,
+    path: &Path,
+    output: Option<&Path>,
+    format: Option<OutputFormat>,
+) -> Result<()> {
+    // Progress goes to stderr so the transcript can be piped
+    let name = if path.as_os_str() == STDIN {
+        "stdin".to_string()
+    } else {
+        path.display().to_string()
+    };
+    let label = format!("Transcribing {name}...");
+    eprint!("{label}");
+    io::stderr().flush()?;
+    let transcript = job.transcribe(path, &label)?;
+    eprint!("\r\x1b[K");
+
+    match output {
+        Some(output) => {
+            let format = format.unwrap_or_else(|| OutputFormat::from_path(output));
+            write_transcript(&transcript, output, format)?;
+            eprintln!("Saved to {}", output.display());
+        }
+        None => print!(
+            "{}",
+            transcript.render(format.unwrap_or(OutputFormat::Text))?
+        ),
+    }
+    Ok(())
+}
+
+/// Transcribe each file to `<dir>/<name>.<format>`. Finished files are recorded in a
+/// manifest in `dir`, so running the same batch again skips them.
+fn transcribe_batch(
+    job: &Job,
+    files: &[PathBuf],
+    dir: &Path,
+    format: OutputFormat,
+    resume: bool,
+) -> Result<()> {
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    // Two inputs named alike would overwrite each other's transcript
+    let mut outputs: HashMap<PathBuf, &Path> = HashMap::new();
+    for file in files {
+        let output = output_path(dir, file, format);
+        if let Some(other) = outputs.insert(output.clone(), file) {
+            anyhow::bail!(
+                "{} and {} would both be written to {}; transcribe them separately",
+                other.display(),
+                file.display(),
+                output.display()
+            );
+        }
+    }
+
+    let manifest_path = dir.join(MANIFEST_NAME);
+    let mut manifest = if resume {
+        Manifest::load(&manifest_path)
+    } else {
+        Manifest::default()
+    };
+
+    let (mut done, mut skipped, mut failed) = (0, 0, 0);
+    for (index, file) in files.iter().enumerate() {
+        let label = format!("[{}/{}] {}", index + 1, files.len(), file.display());
+        let output = output_path(dir, file, format);
+        let key = std::path::absolute(file).unwrap_or_else(|_| file.clone());
+        let entry = ManifestEntry::new(file, &output);
+
+        if entry
+            .as_ref()
+            .is_some_and(|entry| manifest.is_done(&key, entry))
+        {
+            eprintln!("{label} already transcribed, skipping");
+            skipped += 1;
+            continue;
+        }
+
+        eprint!("{label}...");
+        io::stderr().flush()?;
+        let result = job
+            .transcribe(file, &label)
+            .and_then(|transcript| write_transcript(&transcript, &output, format));
+        match result {
+            Ok(()) => {
+                eprintln!("\r\x1b[K{label} -> {}", output.display());
+                if let Some(entry) = entry {
+                    manifest.completed.insert(key, entry);
+                    manifest.save(&manifest_path)?;
+                }
+                done += 1;
+            }
+            Err(e) => {
+                eprintln!("\r\x1b[K{label} failed: {e}");
+                if let Some(hint) = app::error_hint(&e) {
+                    for line in hint.lines() {
+                        eprintln!("   {line}");
+                    }
+                }
+                failed += 1;
+            }
+        }
+    }
+
+    eprintln!("Transcribed {done}, skipped {skipped}, failed {failed}");
+    if failed > 0 {
+        anyhow::bail!("{failed} file(s) failed; run the same command again to retry them");
+    }
+    Ok(())
+}
+
+/// Where the transcript of `file` goes in a batch
+fn output_path(dir: &Path, file: &Path, format: OutputFormat) -> PathBuf {
+    let stem = file.file_stem().unwrap_or(file.as_os_str());
+    // Not `with_extension`, which would also cut dots inside the stem ("talk.v1")
+    dir.join(format!("{}.{}", stem.to_string_lossy(), format.extension()))
+}
+
+fn write_transcript(transcript: &Transcript, path: &Path, format: OutputFormat) -> Result<()> {
+    std::fs::write(path, transcript.render(format)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Inputs of a batch that were transcribed successfully
+#[derive(Default, Serialize, Deserialize)]
+struct Manifest {
+    /// Keyed by the absolute input path
+    completed: BTreeMap<PathBuf, ManifestEntry>,
+}
+
+/// A finished input, with enough to tell whether it changed since
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ManifestEntry {
+    size: u64,
+    /// Modification time in seconds since the Unix epoch
+    modified: u64,
+    output: PathBuf,
+}
+
+impl ManifestEntry {
+    /// Entry for `file` as it is now; `None` if it can't be read
+    fn new(file: &Path, output: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(file).ok()?;
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |duration| duration.as_secs());
+        Some(Self {
+            size: metadata.len(),
+            modified,
+            output: std::path::absolute(output).unwrap_or_else(|_| output.to_path_buf()),
+        })
+    }
+}
+
+impl Manifest {
+    /// Load the manifest, starting over if it is missing or unreadable
+    fn load(path: &Path) -> Self {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Ignoring unreadable {}: {e}", path.display());
+            Self::default()
+        })
+    }
+
+    /// Save atomically, so an interrupted batch never leaves a broken manifest
+    fn save(&self, path: &Path) -> Result<()> {
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", temp.display()))?;
+        std::fs::rename(&temp, path).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    /// Whether `input` was transcribed as it is now and its transcript is still there
+    fn is_done(&self, input: &Path, current: &ManifestEntry) -> bool {
+        self.completed.get(input) == Some(current) && current.output.exists()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_path_keeps_dots_in_name() {
+        let dir = Path::new("out");
+        assert_eq!(
+            output_path(dir, Path::new("talks/talk.v1.mp3"), OutputFormat::Text),
+            Path::new("out/talk.v1.txt")
+        );
+        assert_ne!(
+            output_path(dir, Path::new("talk.v1.mp3"), OutputFormat::Srt),
+            output_path(dir, Path::new("talk.v2.mp3"), OutputFormat::Srt)
+        );
+        assert_eq!(
+            output_path(dir, Path::new("notes"), OutputFormat::Json),
+            Path::new("out/notes.json")
+        );
+    }
+}
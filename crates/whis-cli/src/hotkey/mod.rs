@@ -16,12 +16,25 @@ mod non_linux;
 #[cfg(not(target_os = "linux"))]
 use non_linux as platform;
 
+/// What pressing a registered hotkey does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HotkeyAction {
+    /// Start recording, or stop and transcribe
+    Toggle,
+    /// Abort the transcription in progress
+    Cancel,
+    /// Throw away the recording in progress without transcribing
+    Discard,
+    /// Pause the recording in progress, or resume it
+    Pause,
+}
+
 /// Opaque guard that keeps the hotkey listener alive
 pub struct HotkeyGuard(platform::HotkeyGuard);
 
-/// Setup the hotkey listener.
-/// Returns a receiver for hotkey events and a guard that must be kept alive.
-pub fn setup(hotkey_str: &str) -> Result<(Receiver<()>, HotkeyGuard)> {
-    let (rx, guard) = platform::setup(hotkey_str)?;
+/// Setup the hotkey listener for one or more hotkeys.
+/// Returns a receiver for triggered actions and a guard that must be kept alive.
+pub fn setup(bindings: &[(&str, HotkeyAction)]) -> Result<(Receiver<HotkeyAction>, HotkeyGuard)> {
+    let (rx, guard) = platform::setup(bindings)?;
     Ok((rx, HotkeyGuard(guard)))
 }
@@ -6,37 +6,42 @@ use anyhow::{Context, Result};
 use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, hotkey::HotKey};
 use std::sync::mpsc::Receiver;
 
+use super::HotkeyAction;
+
 pub struct HotkeyGuard {
     _manager: GlobalHotKeyManager,
 }
 
-pub fn setup(hotkey_str: &str) -> Result<(Receiver<()>, HotkeyGuard)> {
-    let converted = convert_to_global_hotkey_format(hotkey_str)?;
-    let hotkey: HotKey = converted
-        .parse()
-        .map_err(|e| anyhow::anyhow!("Invalid hotkey '{}': {:?}", hotkey_str, e))?;
-
+pub fn setup(bindings: &[(&str, HotkeyAction)]) -> Result<(Receiver<HotkeyAction>, HotkeyGuard)> {
     let manager = GlobalHotKeyManager::new()
         .map_err(|e| anyhow::anyhow!("Failed to create hotkey manager: {:?}", e))?;
 
-    manager.register(hotkey.clone()).map_err(|e| {
-        anyhow::anyhow!(
-            "Failed to register hotkey '{}': {:?}\n\n\
-            This may mean the hotkey is already registered by another application.",
-            hotkey_str,
-            e
-        )
-    })?;
+    let mut actions = Vec::with_capacity(bindings.len());
+    for (hotkey_str, action) in bindings {
+        let converted = convert_to_global_hotkey_format(hotkey_str)?;
+        let hotkey: HotKey = converted
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid hotkey '{}': {:?}", hotkey_str, e))?;
+        actions.push((hotkey.id(), *action));
+
+        manager.register(hotkey).map_err(|e| {
+            anyhow::anyhow!(
+                "Failed to register hotkey '{}': {:?}\n\n\
+                This may mean the hotkey is already registered by another application.",
+                hotkey_str,
+                e
+            )
+        })?;
+    }
 
     let receiver = GlobalHotKeyEvent::receiver().clone();
-    let hotkey_id = hotkey.id();
     let (tx, rx) = std::sync::mpsc::channel();
 
     std::thread::spawn(move || {
         loop {
             if let Ok(event) = receiver.recv() {
-                if event.id() == hotkey_id {
-                    let _ = tx.send(());
+                if let Some((_, action)) = actions.iter().find(|(id, _)| *id == event.id()) {
+                    let _ = tx.send(*action);
                 }
             }
         }
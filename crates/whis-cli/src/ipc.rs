@@ -10,13 +10,25 @@ use std::path::PathBuf;
 pub enum IpcMessage {
     Stop,
     Status,
+    /// Abort the transcription in progress
+    Cancel,
+    /// Throw away the recording in progress without transcribing
+    Discard,
+    /// Stop adding audio to the recording in progress, keeping what was recorded
+    Pause,
+    /// Continue a paused recording
+    Resume,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum IpcResponse {
     Success,
     Recording,
+    /// Recording in progress, paused
+    Paused,
     Idle,
+    /// Idle, with the microphone kept open for the warm mic's pre-roll
+    Warm,
     Transcribing,
     Error(String),
 }
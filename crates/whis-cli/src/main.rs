@@ -8,14 +8,40 @@ mod service;
 use anyhow::Result;
 use clap::Parser;
 
-fn main() -> Result<()> {
+fn main() {
     let cli = args::Cli::parse();
 
+    if let Err(err) = run(cli) {
+        app::report_error(&err);
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: args::Cli) -> Result<()> {
     match cli.command {
-        Some(args::Commands::Listen { hotkey }) => commands::listen::run(hotkey),
+        Some(args::Commands::Listen {
+            hotkey,
+            cancel_hotkey,
+            discard_hotkey,
+            pause_hotkey,
+            transcription,
+        }) => commands::listen::run(
+            hotkey,
+            cancel_hotkey,
+            discard_hotkey,
+            pause_hotkey,
+            transcription,
+        ),
         Some(args::Commands::Stop) => commands::stop::run(),
+        Some(args::Commands::Cancel) => commands::cancel::run(),
+        Some(args::Commands::Discard) => commands::discard::run(),
+        Some(args::Commands::Pause) => commands::pause::run(),
+        Some(args::Commands::Resume) => commands::resume::run(),
         Some(args::Commands::Status) => commands::status::run(),
-        Some(args::Commands::Config { api_key, show }) => commands::config::run(api_key, show),
-        None => commands::record_once::run(),
+        Some(args::Commands::Transcribe(transcribe)) => commands::transcribe::run(transcribe),
+        Some(args::Commands::Devices) => commands::devices::run(),
+        Some(args::Commands::Config(config)) => commands::config::run(config),
+        Some(args::Commands::Model { action }) => commands::model::run(action),
+        None => commands::record_once::run(cli.transcription, cli.output, cli.stream),
     }
 }
@@ -2,45 +2,77 @@ use anyhow::{Context, Result};
 use std::io::Write;
 use std::sync::mpsc::Receiver;
 use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
 use tokio::time::sleep;
 
+use crate::hotkey::HotkeyAction;
 use crate::ipc::{IpcMessage, IpcResponse, IpcServer};
 use std::time::Duration;
 use whis_core::{
-    ApiConfig, AudioRecorder, RecordingOutput, copy_to_clipboard, parallel_transcribe,
-    transcribe_audio,
+    AudioRecorder, AutoStop, CancellationToken, EncodingOptions, Settings, Transcript,
+    TranscriptionOptions, TranscriptionProvider, copy_to_clipboard, transcribe_while_recording,
 };
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum ServiceState {
     Idle,
     Recording,
+    /// Recording in progress, but no audio is added until it resumes
+    Paused,
     Transcribing,
 }
 
 pub struct Service {
     state: Arc<Mutex<ServiceState>>,
     recorder: Arc<Mutex<Option<AudioRecorder>>>,
-    config: ApiConfig,
+    provider: Arc<dyn TranscriptionProvider>,
+    options: TranscriptionOptions,
+    encoding: EncodingOptions,
     recording_counter: Arc<Mutex<u32>>,
+    /// Cancels the transcription in progress
+    cancel: Arc<Mutex<Option<CancellationToken>>>,
+    /// Transcribes the current recording chunk by chunk while it is recorded
+    session: Arc<Mutex<Option<JoinHandle<whis_core::Result<Transcript>>>>>,
+    /// Fires when the recording in progress goes silent (auto-stop)
+    silence: Arc<Mutex<Option<oneshot::Receiver<()>>>>,
 }
 
 impl Service {
-    pub fn new(config: ApiConfig) -> Result<Self> {
+    pub fn new(
+        provider: Arc<dyn TranscriptionProvider>,
+        options: TranscriptionOptions,
+        encoding: EncodingOptions,
+    ) -> Result<Self> {
         Ok(Self {
             state: Arc::new(Mutex::new(ServiceState::Idle)),
             recorder: Arc::new(Mutex::new(None)),
-            config,
+            provider,
+            options,
+            encoding,
             recording_counter: Arc::new(Mutex::new(0)),
+            cancel: Arc::new(Mutex::new(None)),
+            session: Arc::new(Mutex::new(None)),
+            silence: Arc::new(Mutex::new(None)),
         })
     }
 
     /// Run the service main loop
-    pub async fn run(&self, hotkey_rx: Option<Receiver<()>>) -> Result<()> {
+    pub async fn run(&self, hotkey_rx: Option<Receiver<HotkeyAction>>) -> Result<()> {
         // Create IPC server
         let ipc_server = IpcServer::new().context("Failed to create IPC server")?;
 
         println!("whis listening. Ctrl+C to stop.");
+        // Be upfront that the microphone is live even while not recording
+        match self.warm_up() {
+            Ok(Some(pre_roll)) => println!(
+                "Warm mic on: the microphone stays open and the last {} ms are kept in memory \
+                 (never saved or sent unless you record)",
+                pre_roll.as_millis()
+            ),
+            Ok(None) => {}
+            Err(e) => println!("Warm mic unavailable: {e}"),
+        }
 
         loop {
             // Check for incoming IPC connections (non-blocking)
@@ -57,13 +89,31 @@ impl Service {
                 }
             }
 
-            // Check for hotkey toggle signal (non-blocking)
-            if let Some(ref rx) = hotkey_rx {
-                if rx.try_recv().is_ok() {
-                    self.handle_toggle().await;
+            // Check for hotkey signals (non-blocking)
+            if let Some(ref rx) = hotkey_rx
+                && let Ok(action) = rx.try_recv()
+            {
+                match action {
+                    HotkeyAction::Toggle => {
+                        self.handle_toggle().await;
+                    }
+                    HotkeyAction::Cancel => {
+                        self.handle_cancel();
+                    }
+                    HotkeyAction::Discard => {
+                        self.handle_discard();
+                    }
+                    HotkeyAction::Pause => {
+                        self.handle_pause_toggle();
+                    }
                 }
             }
 
+            // Stop hands-free once the speaker has gone quiet
+            if self.silence_detected() {
+                self.handle_toggle().await;
+            }
+
             // Small sleep to prevent busy waiting
             sleep(Duration::from_millis(10)).await;
         }
@@ -84,11 +134,17 @@ impl Service {
             IpcMessage::Status => {
                 let state = *self.state.lock().unwrap();
                 match state {
+                    ServiceState::Idle if self.is_warm() => IpcResponse::Warm,
                     ServiceState::Idle => IpcResponse::Idle,
                     ServiceState::Recording => IpcResponse::Recording,
+                    ServiceState::Paused => IpcResponse::Paused,
                     ServiceState::Transcribing => IpcResponse::Transcribing,
                 }
             }
+            IpcMessage::Cancel => self.handle_cancel(),
+            IpcMessage::Discard => self.handle_discard(),
+            IpcMessage::Pause => self.handle_pause(),
+            IpcMessage::Resume => self.handle_resume(),
         }
     }
 
@@ -104,7 +160,7 @@ impl Service {
                     *c += 1;
                     *c
                 };
-                match self.start_recording().await {
+                match self.start_recording(count).await {
                     Ok(_) => {
                         print!("#{count} recording...");
                         let _ = std::io::stdout().flush();
@@ -112,31 +168,53 @@ impl Service {
                     }
                     Err(e) => {
                         println!("#{count} error: {e}");
+                        print_hint(&e);
                         IpcResponse::Error(e.to_string())
                     }
                 }
             }
-            ServiceState::Recording => {
-                // Stop recording and transcribe
-                *self.state.lock().unwrap() = ServiceState::Transcribing;
+            ServiceState::Recording | ServiceState::Paused => {
+                // Stop recording and transcribe (a paused recording ends where it was paused)
                 let count = *self.recording_counter.lock().unwrap();
 
+                let session = match self.stop_recording() {
+                    Ok(session) => session,
+                    Err(e) => {
+                        *self.state.lock().unwrap() = ServiceState::Idle;
+                        println!("\r\x1b[K#{count} error: {e}");
+                        print_hint(&e);
+                        return IpcResponse::Error(e.to_string());
+                    }
+                };
+
+                *self.state.lock().unwrap() = ServiceState::Transcribing;
+                let cancel = self.cancel.lock().unwrap().clone().unwrap_or_default();
+
                 // Show transcribing state (overwrite recording line)
-                print!("\r#{count} transcribing...");
+                print!("\r\x1b[K#{count} transcribing...");
                 let _ = std::io::stdout().flush();
 
-                match self.stop_and_transcribe().await {
-                    Ok(_) => {
-                        *self.state.lock().unwrap() = ServiceState::Idle;
-                        println!("\r#{count} done            ");
-                        IpcResponse::Success
+                // Finish in the background so Cancel can still be received
+                let state = self.state.clone();
+                tokio::spawn(async move {
+                    let result = finish_transcription(session, &cancel).await;
+
+                    let mut state = state.lock().unwrap();
+                    if cancel.is_cancelled() {
+                        // Already reported and back to idle (maybe even recording again)
+                        return;
                     }
-                    Err(e) => {
-                        *self.state.lock().unwrap() = ServiceState::Idle;
-                        println!("\r#{count} error: {e}");
-                        IpcResponse::Error(e.to_string())
+                    *state = ServiceState::Idle;
+                    match result {
+                        Ok(_) => println!("\r#{count} done            "),
+                        Err(e) => {
+                            println!("\r#{count} error: {e}");
+                            print_hint(&e);
+                        }
                     }
-                }
+                });
+
+                IpcResponse::Transcribing
             }
             ServiceState::Transcribing => {
                 // Already transcribing, ignore
@@ -145,56 +223,251 @@ impl Service {
         }
     }
 
-    /// Start recording audio
-    async fn start_recording(&self) -> Result<()> {
-        let mut recorder = AudioRecorder::new()?;
+    /// Abort the transcription in progress and return to idle without touching the clipboard
+    fn handle_cancel(&self) -> IpcResponse {
+        let mut state = self.state.lock().unwrap();
+        if *state != ServiceState::Transcribing {
+            return IpcResponse::Error("No transcription in progress".to_string());
+        }
+
+        if let Some(cancel) = self.cancel.lock().unwrap().take() {
+            cancel.cancel();
+        }
+        *state = ServiceState::Idle;
+
+        let count = *self.recording_counter.lock().unwrap();
+        println!("\r#{count} cancelled       ");
+        IpcResponse::Success
+    }
+
+    /// Throw away the recording in progress and return to idle without calling the API
+    fn handle_discard(&self) -> IpcResponse {
+        let mut state = self.state.lock().unwrap();
+        if !matches!(*state, ServiceState::Recording | ServiceState::Paused) {
+            return IpcResponse::Error("Not recording".to_string());
+        }
+
+        if let Some(recorder) = self.recorder.lock().unwrap().as_mut() {
+            recorder.discard_recording();
+        }
+        self.release_recorder();
+        // Chunks already sent off are abandoned
+        if let Some(cancel) = self.cancel.lock().unwrap().take() {
+            cancel.cancel();
+        }
+        self.session.lock().unwrap().take();
+        *state = ServiceState::Idle;
+
+        let count = *self.recording_counter.lock().unwrap();
+        println!("\r\x1b[K#{count} discarded");
+        IpcResponse::Success
+    }
+
+    /// Pause the recording in progress, keeping the microphone open and what was recorded
+    fn handle_pause(&self) -> IpcResponse {
+        let mut state = self.state.lock().unwrap();
+        if *state != ServiceState::Recording {
+            return IpcResponse::Error("Not recording".to_string());
+        }
+
+        if let Some(recorder) = self.recorder.lock().unwrap().as_mut()
+            && let Err(e) = recorder.pause()
+        {
+            return IpcResponse::Error(e.to_string());
+        }
+        *state = ServiceState::Paused;
+
+        let count = *self.recording_counter.lock().unwrap();
+        print!("\r\x1b[K#{count} paused");
+        let _ = std::io::stdout().flush();
+        IpcResponse::Paused
+    }
+
+    /// Continue a paused recording
+    fn handle_resume(&self) -> IpcResponse {
+        let mut state = self.state.lock().unwrap();
+        if *state != ServiceState::Paused {
+            return IpcResponse::Error("Recording is not paused".to_string());
+        }
+
+        if let Some(recorder) = self.recorder.lock().unwrap().as_mut()
+            && let Err(e) = recorder.resume()
+        {
+            return IpcResponse::Error(e.to_string());
+        }
+        *state = ServiceState::Recording;
+
+        let count = *self.recording_counter.lock().unwrap();
+        print!("\r\x1b[K#{count} recording...");
+        let _ = std::io::stdout().flush();
+        IpcResponse::Recording
+    }
+
+    /// Pause or resume from the hotkey, depending on the current state
+    fn handle_pause_toggle(&self) -> IpcResponse {
+        let paused = *self.state.lock().unwrap() == ServiceState::Paused;
+        if paused {
+            self.handle_resume()
+        } else {
+            self.handle_pause()
+        }
+    }
+
+    /// Whether the recording in progress has been silent for the auto-stop timeout
+    fn silence_detected(&self) -> bool {
+        let mut silence = self.silence.lock().unwrap();
+        let Some(rx) = silence.as_mut() else {
+            return false;
+        };
+        match rx.try_recv() {
+            Ok(()) => {
+                *silence = None;
+                *self.state.lock().unwrap() == ServiceState::Recording
+            }
+            Err(oneshot::error::TryRecvError::Empty) => false,
+            // The recording ended some other way
+            Err(oneshot::error::TryRecvError::Closed) => {
+                *silence = None;
+                false
+            }
+        }
+    }
+
+    /// Open the microphone ahead of time if the warm mic is on, returning the pre-roll
+    fn warm_up(&self) -> Result<Option<Duration>> {
+        let Some(pre_roll) = Settings::load().pre_roll() else {
+            return Ok(None);
+        };
+        let mut recorder = crate::app::new_recorder()?;
+        recorder.warm_up(pre_roll)?;
+        *self.recorder.lock().unwrap() = Some(recorder);
+        Ok(Some(pre_roll))
+    }
+
+    /// Whether the microphone is kept open between recordings
+    fn is_warm(&self) -> bool {
+        self.recorder
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(AudioRecorder::is_warm)
+    }
+
+    /// Drop the recorder after a recording, unless it keeps the microphone warm
+    fn release_recorder(&self) {
+        let mut recorder = self.recorder.lock().unwrap();
+        if !recorder.as_ref().is_some_and(AudioRecorder::is_warm) {
+            *recorder = None;
+        }
+    }
+
+    /// Start recording audio, transcribing completed chunks in the background
+    async fn start_recording(&self, count: u32) -> Result<()> {
+        // A warm microphone is already open and holds the audio from just before now
+        let warm = self.recorder.lock().unwrap().take();
+        let mut recorder = match warm {
+            Some(recorder) => recorder,
+            None => crate::app::new_recorder()?,
+        };
+        let audio = recorder.stream_samples();
+        let mut levels = recorder.watch_levels();
+        *self.silence.lock().unwrap() = AutoStop::from_settings(&Settings::load())
+            .map(|auto_stop| recorder.watch_silence(auto_stop));
         recorder.start_recording()?;
 
+        let cancel = CancellationToken::new();
+        let (sample_rate, channels) = (recorder.sample_rate(), recorder.channels());
+        let provider = self.provider.clone();
+        let options = self.options.clone();
+        let encoding = self.encoding;
+        let session = tokio::spawn({
+            let cancel = cancel.clone();
+            async move {
+                transcribe_while_recording(
+                    provider,
+                    audio,
+                    sample_rate,
+                    channels,
+                    &options,
+                    encoding,
+                    Some(crate::app::print_retries()),
+                    &cancel,
+                )
+                .await
+            }
+        });
+
         *self.recorder.lock().unwrap() = Some(recorder);
+        *self.cancel.lock().unwrap() = Some(cancel);
+        *self.session.lock().unwrap() = Some(session);
         *self.state.lock().unwrap() = ServiceState::Recording;
 
+        // Show elapsed time and input level on the status line
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            while let Some(level) = levels.recv().await {
+                // Checked under the lock so no meter is drawn over the next status line
+                let state = state.lock().unwrap();
+                match *state {
+                    ServiceState::Recording => {}
+                    // No levels arrive while paused; the meter picks up again on resume
+                    ServiceState::Paused => continue,
+                    ServiceState::Idle | ServiceState::Transcribing => break,
+                }
+                print!(
+                    "\r\x1b[K#{count} recording {}",
+                    crate::app::level_meter(&level)
+                );
+                let _ = std::io::stdout().flush();
+            }
+        });
+
         Ok(())
     }
 
-    /// Stop recording and transcribe
-    async fn stop_and_transcribe(&self) -> Result<()> {
-        // Get the recorder
-        let mut recorder = self
-            .recorder
+    /// Stop recording and release the microphone (unless warm), returning the
+    /// transcription to wait for
+    fn stop_recording(&self) -> Result<JoinHandle<whis_core::Result<Transcript>>> {
+        self.recorder
+            .lock()
+            .unwrap()
+            .as_mut()
+            .context("No active recording")?
+            // Closing the sample stream sends off the last chunk
+            .stop_stream();
+        self.release_recorder();
+        self.session
             .lock()
             .unwrap()
             .take()
-            .context("No active recording")?;
-
-        // Stop recording and get the Send-safe recording data
-        // (cpal::Stream is dropped here, making RecordingData movable across threads)
-        let recording_data = recorder.stop_recording()?;
-
-        // Finalize recording (blocking operation, run in tokio blocking task)
-        let audio_result = tokio::task::spawn_blocking(move || recording_data.finalize())
-            .await
-            .context("Failed to join task")??;
-
-        // Transcribe based on output type
-        let api_key = self.config.openai_api_key.clone();
-        let transcription = match audio_result {
-            RecordingOutput::Single(audio_data) => {
-                // Small file - use simple blocking transcription
-                tokio::task::spawn_blocking(move || transcribe_audio(&api_key, audio_data))
-                    .await
-                    .context("Failed to join task")??
-            }
-            RecordingOutput::Chunked(chunks) => {
-                // Large file - use parallel async transcription
-                parallel_transcribe(&api_key, chunks, None).await?
-            }
-        };
+            .context("No active recording")
+    }
+}
 
-        // Copy to clipboard (blocking operation)
-        tokio::task::spawn_blocking(move || copy_to_clipboard(&transcription))
-            .await
-            .context("Failed to join task")??;
+/// Wait for the last chunk to be transcribed, then copy the text to the clipboard
+async fn finish_transcription(
+    session: JoinHandle<whis_core::Result<Transcript>>,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    let transcript = session.await.context("Failed to join task")??;
 
-        Ok(())
+    if cancel.is_cancelled() {
+        return Ok(());
+    }
+
+    // Copy to clipboard (blocking operation)
+    tokio::task::spawn_blocking(move || copy_to_clipboard(&transcript.text))
+        .await
+        .context("Failed to join task")??;
+
+    Ok(())
+}
+
+/// Print the recovery hint for an error below the status line
+fn print_hint(err: &anyhow::Error) {
+    if let Some(hint) = crate::app::error_hint(err) {
+        for line in hint.lines() {
+            println!("   {line}");
+        }
     }
 }
@@ -1,31 +1,266 @@
-use anyhow::{Context, Result};
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::traits::{DeviceTrait, HostTrait};
+use serde::Serialize;
+use std::collections::VecDeque;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::dsp;
+use crate::encoder::{EncodingOptions, EncodingProfile};
+use crate::error::{Result, WhisError};
+use crate::preprocess::{self, Preprocessing};
+use crate::provider::TranscriptionProvider;
+use crate::settings::Settings;
+use crate::source::{AudioSource, InputStream, MicrophoneSource};
+use crate::vad::{self, TimeMap, VadAggressiveness};
+
+pub use crate::encoder::ensure_ffmpeg;
 
 /// Threshold for chunking (files larger than this get split)
-const CHUNK_THRESHOLD_BYTES: usize = 20 * 1024 * 1024; // 20 MB
+pub(crate) const CHUNK_THRESHOLD_BYTES: usize = 20 * 1024 * 1024; // 20 MB
 /// Duration of each chunk in seconds
-const CHUNK_DURATION_SECS: usize = 300; // 5 minutes
-/// Overlap between chunks in seconds (to avoid cutting words)
-const CHUNK_OVERLAP_SECS: usize = 2;
+pub(crate) const CHUNK_DURATION_SECS: usize = 300; // 5 minutes
+/// Shortest chunk, for encoders with high bitrates (e.g. FLAC)
+pub(crate) const MIN_CHUNK_DURATION_SECS: usize = 30;
+/// Chunks end at the quietest moment within this many seconds of the target length
+pub(crate) const CHUNK_SPLIT_WINDOW_SECS: usize = 15;
+/// Sample rate expected by local Whisper models
+pub const WHISPER_SAMPLE_RATE: u32 = 16000;
+/// How often input levels are published while recording
+pub const LEVEL_INTERVAL: Duration = Duration::from_millis(50);
+/// Level reported for digital silence
+const MIN_LEVEL_DB: f32 = -100.0;
 
 /// A chunk of audio data ready for transcription
 #[derive(Clone)]
 pub struct AudioChunk {
-    /// MP3 audio data
+    /// Encoded audio data
     pub data: Vec<u8>,
+    /// File extension of the encoded format (e.g. "mp3")
+    pub extension: &'static str,
     /// Chunk index (0-based, for ordering)
     pub index: usize,
-    /// Whether this chunk has overlap from the previous chunk
-    pub has_leading_overlap: bool,
+    /// Start of this chunk in the recording, in seconds
+    pub offset_secs: f64,
 }
 
 /// Output of a completed recording - either a single file or multiple chunks
 pub enum RecordingOutput {
     /// Small file that can be transcribed directly
-    Single(Vec<u8>),
+    Single {
+        data: Vec<u8>,
+        /// File extension of the encoded format (e.g. "mp3")
+        extension: &'static str,
+    },
     /// Large file split into chunks for parallel transcription
     Chunked(Vec<AudioChunk>),
+    /// Raw 16 kHz mono samples for providers that read PCM directly (no encoding)
+    Pcm(Vec<f32>),
+}
+
+/// An audio input device, as listed by [`list_input_devices`]
+#[derive(Debug, Clone, Serialize)]
+pub struct InputDevice {
+    /// Device name, used to select it in settings
+    pub name: String,
+    /// Whether this is the system's default input device
+    pub is_default: bool,
+}
+
+/// List the input devices of the default audio host
+pub fn list_input_devices() -> Result<Vec<InputDevice>> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+    let devices = host
+        .input_devices()
+        .map_err(|e| WhisError::AudioInput(e.to_string()))?;
+
+    Ok(devices
+        .filter_map(|device| device.name().ok())
+        .map(|name| InputDevice {
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            name,
+        })
+        .collect())
+}
+
+/// When to end a recording hands-free: after `timeout` below `threshold_db`, once speech was heard
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoStop {
+    /// Input level in dBFS below which audio counts as silence
+    pub threshold_db: f32,
+    /// How long the silence has to last
+    pub timeout: Duration,
+}
+
+impl AutoStop {
+    /// Auto-stop settings, or `None` when it is turned off
+    pub fn from_settings(settings: &Settings) -> Option<Self> {
+        let secs = settings.auto_stop_secs.filter(|secs| *secs > 0.0)?;
+        Some(Self {
+            threshold_db: settings.silence_threshold_db,
+            timeout: Duration::from_secs_f32(secs),
+        })
+    }
+}
+
+/// Tracks the input level in the stream callback and fires once silence follows speech
+struct SilenceWatch {
+    threshold_db: f32,
+    timeout_samples: usize,
+    heard_speech: bool,
+    silent_samples: usize,
+    notify: Option<oneshot::Sender<()>>,
+}
+
+impl SilenceWatch {
+    fn new(
+        auto_stop: AutoStop,
+        notify: oneshot::Sender<()>,
+        sample_rate: u32,
+        channels: u16,
+    ) -> Self {
+        let samples_per_sec = sample_rate as f64 * channels as f64;
+        Self {
+            threshold_db: auto_stop.threshold_db,
+            timeout_samples: (auto_stop.timeout.as_secs_f64() * samples_per_sec) as usize,
+            heard_speech: false,
+            silent_samples: 0,
+            notify: Some(notify),
+        }
+    }
+
+    fn process(&mut self, samples: &[f32]) {
+        if self.notify.is_none() || samples.is_empty() {
+            return;
+        }
+        let mean_square = samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32;
+        if 10.0 * mean_square.max(1e-10).log10() > self.threshold_db {
+            self.heard_speech = true;
+            self.silent_samples = 0;
+        } else if self.heard_speech {
+            self.silent_samples += samples.len();
+            if self.silent_samples >= self.timeout_samples
+                && let Some(notify) = self.notify.take()
+            {
+                let _ = notify.send(());
+            }
+        }
+    }
+}
+
+/// Input level of the last [`LEVEL_INTERVAL`] of a recording, for level meters
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct InputLevel {
+    /// RMS level in dBFS
+    pub rms_db: f32,
+    /// Peak level in dBFS
+    pub peak_db: f32,
+    /// Time recorded so far, in seconds
+    pub elapsed_secs: f64,
+}
+
+/// Measures the input in the stream callback and publishes it every [`LEVEL_INTERVAL`]
+struct LevelMeter {
+    sender: mpsc::UnboundedSender<InputLevel>,
+    samples_per_sec: f64,
+    interval_samples: usize,
+    /// Samples seen before the current interval
+    elapsed_samples: usize,
+    /// Samples, sum of squares and peak of the current interval
+    count: usize,
+    sum_squares: f32,
+    peak: f32,
+}
+
+impl LevelMeter {
+    fn new(sender: mpsc::UnboundedSender<InputLevel>, sample_rate: u32, channels: u16) -> Self {
+        let samples_per_sec = sample_rate as f64 * channels as f64;
+        Self {
+            sender,
+            samples_per_sec,
+            interval_samples: ((LEVEL_INTERVAL.as_secs_f64() * samples_per_sec) as usize).max(1),
+            elapsed_samples: 0,
+            count: 0,
+            sum_squares: 0.0,
+            peak: 0.0,
+        }
+    }
+
+    fn process(&mut self, samples: &[f32]) {
+        for &sample in samples {
+            self.sum_squares += sample * sample;
+            self.peak = self.peak.max(sample.abs());
+            self.count += 1;
+            if self.count == self.interval_samples {
+                self.publish();
+            }
+        }
+    }
+
+    fn publish(&mut self) {
+        let to_db = |value: f32| (20.0 * value.log10()).max(MIN_LEVEL_DB);
+        self.elapsed_samples += self.count;
+        let level = InputLevel {
+            rms_db: to_db((self.sum_squares / self.count as f32).sqrt()),
+            peak_db: to_db(self.peak),
+            elapsed_secs: self.elapsed_samples as f64 / self.samples_per_sec,
+        };
+        // Nobody may be watching anymore; recording continues regardless
+        let _ = self.sender.send(level);
+        self.count = 0;
+        self.sum_squares = 0.0;
+        self.peak = 0.0;
+    }
+}
+
+/// What the input callback does with captured audio. Set up anew for every recording,
+/// so a warm stream (see [`AudioRecorder::warm_up`]) can stay open in between.
+#[derive(Default)]
+struct Capture {
+    /// Whether a recording is in progress; otherwise audio only fills the pre-roll
+    recording: bool,
+    /// Whether the recording in progress is paused; audio is dropped until it resumes
+    paused: bool,
+    /// Captured samples, kept for `stop_recording` when `buffer_samples` is set
+    samples: Vec<f32>,
+    buffer_samples: bool,
+    tap: Option<mpsc::UnboundedSender<Vec<f32>>>,
+    silence: Option<SilenceWatch>,
+    meter: Option<LevelMeter>,
+    /// The most recent audio while warm and not recording
+    pre_roll: VecDeque<f32>,
+    /// Samples kept in `pre_roll`; zero when the microphone isn't warm
+    pre_roll_len: usize,
+}
+
+impl Capture {
+    fn process(&mut self, captured: Vec<f32>) {
+        if !self.recording {
+            if self.pre_roll_len > 0 {
+                self.pre_roll.extend(captured);
+                let excess = self.pre_roll.len().saturating_sub(self.pre_roll_len);
+                self.pre_roll.drain(..excess);
+            }
+            return;
+        }
+        if self.paused {
+            return;
+        }
+        if let Some(silence) = &mut self.silence {
+            silence.process(&captured);
+        }
+        if let Some(meter) = &mut self.meter {
+            meter.process(&captured);
+        }
+        if self.buffer_samples {
+            self.samples.extend_from_slice(&captured);
+        }
+        if let Some(tap) = &self.tap {
+            // The receiver may already be gone; recording continues regardless
+            let _ = tap.send(captured);
+        }
+    }
 }
 
 /// Recording data extracted from AudioRecorder after stopping.
@@ -37,52 +272,217 @@ pub struct RecordingData {
 }
 
 pub struct AudioRecorder {
-    samples: Arc<Mutex<Vec<f32>>>,
+    /// Shared with the input callback
+    capture: Arc<Mutex<Capture>>,
     sample_rate: u32,
     channels: u16,
-    stream: Option<cpal::Stream>,
+    stream: Option<InputStream>,
+    /// Where the audio comes from (the default microphone unless set)
+    source: Box<dyn AudioSource>,
+    /// Receives a copy of every buffer from the input callback (for streaming)
+    sample_tap: Option<mpsc::UnboundedSender<Vec<f32>>>,
+    /// Whether captured samples are kept for `stop_recording` (off when only streamed)
+    buffer_samples: bool,
+    /// Notified when the input goes quiet after speech (hands-free stop)
+    auto_stop: Option<(AutoStop, oneshot::Sender<()>)>,
+    /// Receives input levels and elapsed time while recording
+    level_tap: Option<mpsc::UnboundedSender<InputLevel>>,
+    /// Audio kept from before the start of a recording; zero when the microphone isn't warm
+    pre_roll: Duration,
 }
 
 impl AudioRecorder {
     pub fn new() -> Result<Self> {
         Ok(AudioRecorder {
-            samples: Arc::new(Mutex::new(Vec::new())),
+            capture: Arc::new(Mutex::new(Capture::default())),
             sample_rate: 44100, // Default sample rate
             channels: 1,        // Default channels
             stream: None,
+            source: Box::new(MicrophoneSource::default()),
+            sample_tap: None,
+            buffer_samples: true,
+            auto_stop: None,
+            level_tap: None,
+            pre_roll: Duration::ZERO,
         })
     }
 
-    pub fn start_recording(&mut self) -> Result<()> {
-        let host = cpal::default_host();
-        let device = host
-            .default_input_device()
-            .context("No input device available")?;
+    /// Record from the named input device instead of the default one.
+    /// Falls back to the default device if it isn't connected. A warm microphone
+    /// keeps using the old device until it is warmed up again.
+    pub fn set_input_device(&mut self, name: Option<String>) {
+        self.source = Box::new(MicrophoneSource::new(name));
+    }
 
-        let config = device
-            .default_input_config()
-            .context("Failed to get default input config")?;
+    /// Record from `source` instead of a microphone (e.g. a file or test tone, see
+    /// [`source::from_settings`](crate::source::from_settings)). Like a new input
+    /// device, it takes over when the stream is opened next.
+    pub fn set_source(&mut self, source: Box<dyn AudioSource>) {
+        self.source = source;
+    }
 
-        self.sample_rate = config.sample_rate().0;
-        self.channels = config.channels();
+    /// Receive interleaved samples as they are captured, in addition to buffering them.
+    /// Call before `start_recording`; the channel closes when recording stops.
+    pub fn tap_samples(&mut self) -> mpsc::UnboundedReceiver<Vec<f32>> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.sample_tap = Some(tx);
+        self.buffer_samples = true;
+        rx
+    }
 
-        let samples = self.samples.clone();
-        samples.lock().unwrap().clear();
+    /// Receive interleaved samples as they are captured, without keeping a copy, so long
+    /// recordings don't pile up in memory. Call before `start_recording` and end the
+    /// recording with `stop_stream`, which closes the channel.
+    pub fn stream_samples(&mut self) -> mpsc::UnboundedReceiver<Vec<f32>> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.sample_tap = Some(tx);
+        self.buffer_samples = false;
+        rx
+    }
 
-        let stream = match config.sample_format() {
-            cpal::SampleFormat::F32 => {
-                self.build_stream::<f32>(&device, &config.into(), samples)?
-            }
-            cpal::SampleFormat::I16 => {
-                self.build_stream::<i16>(&device, &config.into(), samples)?
-            }
-            cpal::SampleFormat::U16 => {
-                self.build_stream::<u16>(&device, &config.into(), samples)?
-            }
-            _ => anyhow::bail!("Unsupported sample format"),
+    /// Get notified when the input stays silent for `auto_stop.timeout` after speech.
+    /// Call before `start_recording`; fires at most once per recording.
+    pub fn watch_silence(&mut self, auto_stop: AutoStop) -> oneshot::Receiver<()> {
+        let (tx, rx) = oneshot::channel();
+        self.auto_stop = Some((auto_stop, tx));
+        rx
+    }
+
+    /// Receive the input level and elapsed time every [`LEVEL_INTERVAL`] while recording.
+    /// Call before `start_recording`; the channel closes when recording stops.
+    pub fn watch_levels(&mut self) -> mpsc::UnboundedReceiver<InputLevel> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.level_tap = Some(tx);
+        rx
+    }
+
+    /// Sample rate of the input device (valid once recording has started)
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Channel count of the input device (valid once recording has started)
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Keep the microphone open between recordings and put the last `pre_roll` of
+    /// audio in front of the next recording, so the first word isn't lost while the
+    /// stream starts. The audio only ever sits in memory and is overwritten
+    /// continuously; `cool_down` releases the microphone.
+    pub fn warm_up(&mut self, pre_roll: Duration) -> Result<()> {
+        if self.stream.is_none() {
+            self.open_stream()?;
+        }
+        self.pre_roll = pre_roll;
+        self.capture.lock().unwrap().pre_roll_len = self.pre_roll_len();
+        Ok(())
+    }
+
+    /// Samples kept in the pre-roll while warm
+    fn pre_roll_len(&self) -> usize {
+        let frames = (self.pre_roll.as_secs_f64() * self.sample_rate as f64) as usize;
+        frames * self.channels as usize
+    }
+
+    /// Stop keeping audio from before recordings and release the microphone
+    /// (once the recording in progress, if any, ends)
+    pub fn cool_down(&mut self) {
+        self.pre_roll = Duration::ZERO;
+        let recording = {
+            let mut capture = self.capture.lock().unwrap();
+            capture.pre_roll_len = 0;
+            capture.pre_roll = VecDeque::new();
+            capture.recording
         };
+        // Dropping the stream waits for its callback, which needs the capture lock
+        if !recording {
+            self.stream = None;
+        }
+    }
 
-        stream.play()?;
+    /// Whether the microphone is kept open between recordings
+    pub fn is_warm(&self) -> bool {
+        !self.pre_roll.is_zero() && self.stream.is_some()
+    }
+
+    /// Stop adding audio to the recording in progress without closing the stream.
+    /// Samples captured so far are kept; the elapsed time and auto-stop silence
+    /// don't advance while paused.
+    pub fn pause(&mut self) -> Result<()> {
+        let mut capture = self.capture.lock().unwrap();
+        if !capture.recording {
+            return Err(WhisError::NotRecording);
+        }
+        capture.paused = true;
+        Ok(())
+    }
+
+    /// Continue a paused recording where it left off
+    pub fn resume(&mut self) -> Result<()> {
+        let mut capture = self.capture.lock().unwrap();
+        if !capture.recording {
+            return Err(WhisError::NotRecording);
+        }
+        capture.paused = false;
+        Ok(())
+    }
+
+    /// Whether the recording in progress is paused
+    pub fn is_paused(&self) -> bool {
+        self.capture.lock().unwrap().paused
+    }
+
+    pub fn start_recording(&mut self) -> Result<()> {
+        if self.stream.is_none() {
+            self.open_stream()?;
+        }
+
+        let silence = self.auto_stop.take().map(|(auto_stop, notify)| {
+            SilenceWatch::new(auto_stop, notify, self.sample_rate, self.channels)
+        });
+        let meter = self
+            .level_tap
+            .take()
+            .map(|sender| LevelMeter::new(sender, self.sample_rate, self.channels));
+
+        let mut capture = self.capture.lock().unwrap();
+        let pre_roll: Vec<f32> = capture.pre_roll.drain(..).collect();
+        *capture = Capture {
+            recording: true,
+            paused: false,
+            samples: Vec::new(),
+            buffer_samples: self.buffer_samples,
+            tap: self.sample_tap.take(),
+            silence,
+            meter,
+            pre_roll: VecDeque::new(),
+            pre_roll_len: self.pre_roll_len(),
+        };
+        // Audio from just before the start (or since the stream opened) comes first,
+        // without a gap to the live stream
+        if !pre_roll.is_empty() {
+            capture.process(pre_roll);
+        }
+
+        Ok(())
+    }
+
+    /// Open the input stream; until a recording starts, audio only fills the pre-roll.
+    /// That keeps everything until the caller sets its length, so no audio from the
+    /// first buffers is lost while a recording is set up.
+    fn open_stream(&mut self) -> Result<()> {
+        *self.capture.lock().unwrap() = Capture {
+            pre_roll_len: usize::MAX,
+            ..Capture::default()
+        };
+
+        let capture = self.capture.clone();
+        let stream = self.source.open(Box::new(move |captured| {
+            capture.lock().unwrap().process(captured);
+        }))?;
+        self.sample_rate = stream.sample_rate();
+        self.channels = stream.channels();
 
         // Store stream to keep it alive; dropping it will release the microphone
         self.stream = Some(stream);
@@ -90,47 +490,30 @@ impl AudioRecorder {
         Ok(())
     }
 
-    fn build_stream<T>(
-        &self,
-        device: &cpal::Device,
-        config: &cpal::StreamConfig,
-        samples: Arc<Mutex<Vec<f32>>>,
-    ) -> Result<cpal::Stream>
-    where
-        T: cpal::Sample + cpal::SizedSample,
-        f32: cpal::FromSample<T>,
-    {
-        let err_fn = |err| eprintln!("Error in audio stream: {err}");
-
-        let stream = device.build_input_stream(
-            config,
-            move |data: &[T], _: &cpal::InputCallbackInfo| {
-                let mut samples = samples.lock().unwrap();
-                for &sample in data {
-                    samples.push(cpal::Sample::from_sample(sample));
-                }
-            },
-            err_fn,
-            None,
-        )?;
-
-        Ok(stream)
+    /// End the recording in progress and return the buffered samples. The sample and
+    /// level channels close; the microphone is released unless it is warm.
+    fn end_recording(&mut self) -> Vec<f32> {
+        if self.pre_roll.is_zero() {
+            self.stream = None;
+        }
+        self.sample_tap = None;
+        self.auto_stop = None;
+        self.level_tap = None;
+
+        let mut capture = self.capture.lock().unwrap();
+        capture.recording = false;
+        capture.paused = false;
+        capture.tap = None;
+        capture.silence = None;
+        capture.meter = None;
+        std::mem::take(&mut capture.samples)
     }
 
     /// Stop recording and return the recording data.
-    /// The stream is dropped here, making the returned RecordingData Send-safe.
     pub fn stop_recording(&mut self) -> Result<RecordingData> {
-        // Drop the stream first to release the microphone
-        self.stream = None;
-
-        // Take ownership of samples and clear the buffer
-        let samples: Vec<f32> = {
-            let mut guard = self.samples.lock().unwrap();
-            std::mem::take(&mut *guard)
-        };
-
+        let samples = self.end_recording();
         if samples.is_empty() {
-            anyhow::bail!("No audio data recorded");
+            return Err(WhisError::EmptyRecording);
         }
 
         Ok(RecordingData {
@@ -140,132 +523,306 @@ impl AudioRecorder {
         })
     }
 
+    /// Stop a recording started with `stream_samples`, releasing the microphone
+    /// (unless it is warm) and closing the sample channel.
+    pub fn stop_stream(&mut self) {
+        self.end_recording();
+    }
+
+    /// Stop recording and throw the captured audio away, releasing the microphone
+    /// unless it is warm.
+    pub fn discard_recording(&mut self) {
+        self.end_recording();
+    }
+
     /// Stop recording and finalize in one step (convenience method for single-threaded use).
     pub fn finalize_recording(&mut self) -> Result<RecordingOutput> {
-        self.stop_recording()?.finalize()
+        self.stop_recording()?.finalize(&EncodingOptions::default())
     }
 }
 
 impl RecordingData {
-    /// Finalize the recording by converting samples to MP3.
+    /// Recording from interleaved samples captured elsewhere
+    pub fn new(samples: Vec<f32>, sample_rate: u32, channels: u16) -> Self {
+        Self {
+            samples,
+            sample_rate,
+            channels,
+        }
+    }
+
+    /// Interleaved samples, sample rate and channel count
+    pub(crate) fn into_parts(self) -> (Vec<f32>, u32, u16) {
+        (self.samples, self.sample_rate, self.channels)
+    }
+
+    /// Run the enabled clean-up stages (noise suppression, high-pass, loudness
+    /// normalization). The recording keeps its length.
+    pub fn preprocess(self, stages: &Preprocessing) -> Result<Self> {
+        if !stages.is_enabled() {
+            return Ok(self);
+        }
+        let samples =
+            preprocess::preprocess(self.samples, self.sample_rate, self.channels, stages)?;
+        Ok(Self { samples, ..self })
+    }
+
+    /// Cut silent stretches before encoding. The returned map moves transcript
+    /// timestamps back onto the original recording timeline.
+    pub fn trim_silence(self, aggressiveness: VadAggressiveness) -> (Self, TimeMap) {
+        let (samples, map) = vad::trim_silence(
+            self.samples,
+            self.sample_rate,
+            self.channels,
+            aggressiveness,
+        );
+        (Self { samples, ..self }, map)
+    }
+
+    /// Finalize the recording in the format the provider reads:
+    /// raw PCM for local models, audio encoded with `options` (possibly chunked) otherwise.
+    pub fn finalize_for(
+        self,
+        provider: &dyn TranscriptionProvider,
+        options: &EncodingOptions,
+    ) -> Result<RecordingOutput> {
+        if provider.accepts_pcm() {
+            self.finalize_pcm()
+        } else {
+            self.finalize(options)
+        }
+    }
+
+    /// Finalize the recording as 16 kHz mono PCM, skipping encoding entirely
+    pub fn finalize_pcm(self) -> Result<RecordingOutput> {
+        Ok(RecordingOutput::Pcm(
+            self.resampled(WHISPER_SAMPLE_RATE)?.samples,
+        ))
+    }
+
+    /// Downmix to mono and resample to `sample_rate`
+    fn resampled(self, sample_rate: u32) -> Result<Self> {
+        let mono = dsp::downmix(&self.samples, self.channels);
+        Ok(Self {
+            samples: dsp::resample(&mono, self.sample_rate, sample_rate)?,
+            sample_rate,
+            channels: 1,
+        })
+    }
+
+    /// Convert the recording to the rate and channel layout of `profile`
+    fn prepare(self, profile: EncodingProfile) -> Result<Self> {
+        match profile.sample_rate() {
+            Some(sample_rate) => self.resampled(sample_rate),
+            None => Ok(self),
+        }
+    }
+
+    /// Finalize the recording by encoding the samples.
     /// This is Send-safe and can be called from spawn_blocking.
-    pub fn finalize(self) -> Result<RecordingOutput> {
+    pub fn finalize(self, options: &EncodingOptions) -> Result<RecordingOutput> {
+        let recording = self.prepare(options.profile)?;
+        let extension = options.encoder.extension();
+
         // Try to convert the entire recording first
-        let mp3_data = self.samples_to_mp3(&self.samples, "main")?;
+        let encoded = recording.encode(options, &recording.samples)?;
 
         // If at or under threshold, return as single file (fast path)
-        if mp3_data.len() <= CHUNK_THRESHOLD_BYTES {
-            return Ok(RecordingOutput::Single(mp3_data));
+        if encoded.len() <= CHUNK_THRESHOLD_BYTES {
+            return Ok(RecordingOutput::Single {
+                data: encoded,
+                extension,
+            });
         }
 
-        // File is too large - need to chunk it
-        let samples_per_second = self.sample_rate as usize * self.channels as usize;
-        let chunk_samples = CHUNK_DURATION_SECS * samples_per_second;
-        let overlap_samples = CHUNK_OVERLAP_SECS * samples_per_second;
+        // File is too large - need to chunk it. Size chunks by the bitrate we
+        // actually got, so each one stays under the threshold.
+        let frames_per_second = recording.sample_rate as usize;
+        let channels = recording.channels as usize;
+        let total_frames = recording.samples.len() / channels;
+        let duration_secs = (total_frames / frames_per_second).max(1);
+        let bytes_per_second = (encoded.len() / duration_secs).max(1);
+        let size_limit_secs = CHUNK_THRESHOLD_BYTES / bytes_per_second;
+        let chunk_secs = size_limit_secs.clamp(MIN_CHUNK_DURATION_SECS, CHUNK_DURATION_SECS);
+        let longest_secs = (chunk_secs + CHUNK_SPLIT_WINDOW_SECS)
+            .min(size_limit_secs)
+            .max(chunk_secs);
+        let shortest_secs = chunk_secs - CHUNK_SPLIT_WINDOW_SECS.min(chunk_secs / 2);
 
         let mut chunks = Vec::new();
         let mut chunk_start = 0usize;
-        let mut chunk_index = 0usize;
-
-        while chunk_start < self.samples.len() {
-            let chunk_end = (chunk_start + chunk_samples).min(self.samples.len());
-            let chunk_slice = &self.samples[chunk_start..chunk_end];
 
-            // Convert this chunk to MP3
-            let chunk_mp3 = self.samples_to_mp3(chunk_slice, &format!("chunk{chunk_index}"))?;
+        while chunk_start < total_frames {
+            let chunk_end = if total_frames - chunk_start <= chunk_secs * frames_per_second {
+                total_frames
+            } else {
+                // Cut at a pause near the target length so no word is split
+                let earliest = chunk_start + shortest_secs * frames_per_second;
+                let latest = (chunk_start + longest_secs * frames_per_second).min(total_frames);
+                dsp::quietest_frame(
+                    &recording.samples,
+                    recording.channels,
+                    recording.sample_rate,
+                    earliest..latest,
+                )
+            };
+            let chunk_slice = &recording.samples[chunk_start * channels..chunk_end * channels];
 
             chunks.push(AudioChunk {
-                data: chunk_mp3,
-                index: chunk_index,
-                has_leading_overlap: chunk_index > 0,
+                data: recording.encode(options, chunk_slice)?,
+                extension,
+                index: chunks.len(),
+                offset_secs: chunk_start as f64 / frames_per_second as f64,
             });
 
-            chunk_index += 1;
-
-            // Check if we've reached the end
-            if chunk_end >= self.samples.len() {
-                break;
-            }
-
-            // Move to next chunk, stepping back by overlap amount
-            chunk_start = chunk_end.saturating_sub(overlap_samples);
+            chunk_start = chunk_end;
         }
 
         Ok(RecordingOutput::Chunked(chunks))
     }
 
-    /// Convert raw f32 samples to MP3 data
-    fn samples_to_mp3(&self, samples: &[f32], suffix: &str) -> Result<Vec<u8>> {
-        // Convert f32 samples to i16 for WAV format
-        let i16_samples: Vec<i16> = samples
-            .iter()
-            .map(|&s| {
-                let clamped = s.clamp(-1.0, 1.0);
-                (clamped * i16::MAX as f32) as i16
-            })
-            .collect();
-
-        // Use unique temp file names to support parallel FFmpeg calls
-        let temp_dir = std::env::temp_dir();
-        let unique_id = format!(
-            "{}_{}_{suffix}",
-            std::process::id(),
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_nanos(),
-        );
-        let wav_path = temp_dir.join(format!("whis_{unique_id}.wav"));
-        let mp3_path = temp_dir.join(format!("whis_{unique_id}.mp3"));
-
-        {
-            let spec = hound::WavSpec {
-                channels: self.channels,
-                sample_rate: self.sample_rate,
-                bits_per_sample: 16,
-                sample_format: hound::SampleFormat::Int,
-            };
+    /// Encode the whole recording as one file, however large it gets
+    pub(crate) fn encode_whole(self, options: &EncodingOptions) -> Result<Vec<u8>> {
+        let recording = self.prepare(options.profile)?;
+        recording.encode(options, &recording.samples)
+    }
 
-            let mut writer = hound::WavWriter::create(&wav_path, spec)?;
-            for sample in i16_samples {
-                writer.write_sample(sample)?;
-            }
-            writer.finalize()?;
+    fn encode(&self, options: &EncodingOptions, samples: &[f32]) -> Result<Vec<u8>> {
+        options.encoder.encode(
+            samples,
+            self.sample_rate,
+            self.channels,
+            options.profile.bitrate_kbps(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Source whose audio the test delivers by hand through the returned sink
+    struct ManualSource {
+        sink: Arc<Mutex<Option<crate::source::SampleSink>>>,
+    }
+
+    impl AudioSource for ManualSource {
+        fn open(&self, sink: crate::source::SampleSink) -> Result<InputStream> {
+            *self.sink.lock().unwrap() = Some(sink);
+            // Keeps the stream alive without delivering anything itself
+            Ok(InputStream::generated(1000, 1, |_| {}, Box::new(|_| {})))
+        }
+    }
+
+    /// Recorder reading from a [`ManualSource`], and a way to feed it audio
+    fn manual_recorder() -> (AudioRecorder, impl Fn(Vec<f32>)) {
+        let sink = Arc::new(Mutex::new(None));
+        let mut recorder = AudioRecorder::new().unwrap();
+        recorder.set_source(Box::new(ManualSource { sink: sink.clone() }));
+        let feed = move |samples| {
+            let mut sink = sink.lock().unwrap();
+            (sink.as_mut().expect("stream not open"))(samples)
+        };
+        (recorder, feed)
+    }
+
+    /// Buffers sent so far
+    fn received(rx: &mut mpsc::UnboundedReceiver<Vec<f32>>) -> Vec<Vec<f32>> {
+        let mut buffers = Vec::new();
+        while let Ok(buffer) = rx.try_recv() {
+            buffers.push(buffer);
         }
+        buffers
+    }
+
+    #[test]
+    fn test_audio_while_paused_is_dropped() {
+        let (mut recorder, feed) = manual_recorder();
+        let mut tap = recorder.tap_samples();
+        recorder.start_recording().unwrap();
+        feed(vec![0.1; 4]);
+        recorder.pause().unwrap();
+        feed(vec![0.2; 4]);
+        recorder.resume().unwrap();
+        feed(vec![0.3; 4]);
+
+        let (samples, _, _) = recorder.stop_recording().unwrap().into_parts();
+        assert_eq!(samples, [[0.1; 4], [0.3; 4]].concat());
+        assert_eq!(received(&mut tap), [vec![0.1; 4], vec![0.3; 4]]);
+
+        // Likewise when the audio is only streamed
+        let mut stream = recorder.stream_samples();
+        recorder.start_recording().unwrap();
+        feed(vec![0.4; 4]);
+        recorder.pause().unwrap();
+        feed(vec![0.5; 4]);
+        recorder.resume().unwrap();
+        feed(vec![0.6; 4]);
+        recorder.stop_stream();
+        assert_eq!(received(&mut stream), [vec![0.4; 4], vec![0.6; 4]]);
+    }
 
-        // Convert WAV to MP3 using FFmpeg
-        let output = std::process::Command::new("ffmpeg")
-            .args([
-                "-hide_banner",
-                "-loglevel",
-                "error",
-                "-i",
-                wav_path.to_str().unwrap(),
-                "-codec:a",
-                "libmp3lame",
-                "-b:a",
-                "128k",
-                "-y",
-                mp3_path.to_str().unwrap(),
-            ])
-            .output()
-            .context("Failed to execute ffmpeg. Make sure ffmpeg is installed.")?;
-
-        // Clean up the temporary WAV file
-        let _ = std::fs::remove_file(&wav_path);
-
-        if !output.status.success() {
-            let _ = std::fs::remove_file(&mp3_path);
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("FFmpeg conversion failed: {stderr}");
+    #[test]
+    fn test_pre_roll_stays_bounded() {
+        let (mut recorder, feed) = manual_recorder();
+        // 10 ms at the 1 kHz of the manual source
+        recorder.warm_up(Duration::from_millis(10)).unwrap();
+        for i in 0..100 {
+            feed(vec![i as f32; 4]);
         }
 
-        // Read the MP3 file
-        let mp3_data = std::fs::read(&mp3_path).context("Failed to read converted MP3 file")?;
+        let capture = recorder.capture.lock().unwrap();
+        assert_eq!(capture.pre_roll_len, 10);
+        let expected: Vec<f32> =
+            [97.0, 97.0, 98.0, 98.0, 98.0, 98.0, 99.0, 99.0, 99.0, 99.0].into();
+        assert_eq!(capture.pre_roll, expected);
+    }
 
-        // Clean up the temporary MP3 file
-        let _ = std::fs::remove_file(&mp3_path);
+    #[test]
+    fn test_pre_roll_is_prepended_once() {
+        let (mut recorder, feed) = manual_recorder();
+        recorder.warm_up(Duration::from_millis(10)).unwrap();
+        feed(vec![0.1; 10]);
+
+        let mut tap = recorder.tap_samples();
+        recorder.start_recording().unwrap();
+        feed(vec![0.2; 4]);
+        let (samples, _, _) = recorder.stop_recording().unwrap().into_parts();
+        assert_eq!(samples, [vec![0.1; 10], vec![0.2; 4]].concat());
+        assert_eq!(received(&mut tap), [vec![0.1; 10], vec![0.2; 4]]);
+
+        // The next recording only gets what came in after the last one ended
+        feed(vec![0.3; 2]);
+        recorder.start_recording().unwrap();
+        feed(vec![0.4; 4]);
+        let (samples, _, _) = recorder.stop_recording().unwrap().into_parts();
+        assert_eq!(samples, [vec![0.3; 2], vec![0.4; 4]].concat());
+    }
+
+    #[test]
+    fn test_pre_roll_is_bounded_once_warm() {
+        let (mut recorder, feed) = manual_recorder();
+        // The stream opens keeping everything, so nothing is lost before the
+        // length is known, but warming up bounds it right away
+        recorder.warm_up(Duration::from_millis(10)).unwrap();
+        assert_eq!(recorder.capture.lock().unwrap().pre_roll_len, 10);
+        feed(vec![0.1; 100_000]);
+        assert_eq!(recorder.capture.lock().unwrap().pre_roll.len(), 10);
+
+        // Still bounded after a recording and after warming up again
+        recorder.start_recording().unwrap();
+        recorder.stop_stream();
+        feed(vec![0.1; 100_000]);
+        assert_eq!(recorder.capture.lock().unwrap().pre_roll.len(), 10);
+        recorder.warm_up(Duration::from_millis(5)).unwrap();
+        feed(vec![0.1; 100_000]);
+        assert_eq!(recorder.capture.lock().unwrap().pre_roll.len(), 5);
+    }
 
-        Ok(mp3_data)
+    #[test]
+    fn test_pause_without_recording_is_an_error() {
+        let mut recorder = AudioRecorder::new().unwrap();
+        assert!(matches!(recorder.pause(), Err(WhisError::NotRecording)));
+        assert!(matches!(recorder.resume(), Err(WhisError::NotRecording)));
     }
 }
@@ -1,8 +1,10 @@
-use anyhow::{Context, Result};
+use anyhow::Context;
 use arboard::Clipboard;
 use std::io::Write;
 use std::process::{Command, Stdio};
 
+use crate::error::{Result, WhisError};
+
 /// Check if running inside a Flatpak sandbox
 fn is_flatpak() -> bool {
     std::path::Path::new("/.flatpak-info").exists()
@@ -13,7 +15,7 @@ fn is_flatpak() -> bool {
 /// In Flatpak, we bundle wl-clipboard and call wl-copy directly.
 /// This is required because GNOME/Mutter does not implement the wlr-data-control
 /// Wayland protocol that arboard's wayland-data-control feature requires.
-fn copy_via_wl_copy(text: &str) -> Result<()> {
+fn copy_via_wl_copy(text: &str) -> anyhow::Result<()> {
     let mut child = Command::new("wl-copy")
         .stdin(Stdio::piped())
         .spawn()
@@ -37,14 +39,16 @@ pub fn copy_to_clipboard(text: &str) -> Result<()> {
     // In Flatpak, use bundled wl-copy directly.
     // This is necessary because GNOME doesn't support wlr-data-control protocol.
     if is_flatpak() {
-        return copy_via_wl_copy(text);
+        return copy_via_wl_copy(text)
+            .map_err(|e| WhisError::ClipboardUnavailable(format!("{e:#}")));
     }
 
     // Standard approach for non-Flatpak environments
-    let mut clipboard = Clipboard::new().context("Failed to access clipboard")?;
+    let mut clipboard =
+        Clipboard::new().map_err(|e| WhisError::ClipboardUnavailable(e.to_string()))?;
     clipboard
         .set_text(text)
-        .context("Failed to copy text to clipboard")?;
+        .map_err(|e| WhisError::ClipboardUnavailable(e.to_string()))?;
 
     Ok(())
 }
@@ -1,17 +1,139 @@
-use anyhow::{Context, Result};
 use std::env;
+use std::sync::Arc;
+
+use crate::error::{Result, WhisError};
+use crate::provider::{OpenAIProvider, TranscriptionProvider};
+use crate::settings::Settings;
 
 pub struct ApiConfig {
-    pub openai_api_key: String,
+    /// API key sent as a Bearer token (optional for self-hosted servers)
+    pub api_key: Option<String>,
+    /// Base URL of an OpenAI-compatible server; `None` uses api.openai.com
+    pub base_url: Option<String>,
+    /// Transcription model name; `None` uses the provider default
+    pub model: Option<String>,
 }
 
 impl ApiConfig {
     pub fn from_env() -> Result<Self> {
         dotenvy::dotenv().ok(); // Load .env file if it exists
 
-        let openai_api_key = env::var("OPENAI_API_KEY")
-            .context("OPENAI_API_KEY not found. Please set it in .env file or environment")?;
+        let base_url = env::var("OPENAI_BASE_URL").ok();
+        let api_key = env::var("OPENAI_API_KEY").ok();
+
+        // Self-hosted servers usually don't need a key
+        if api_key.is_none() && base_url.is_none() {
+            return Err(WhisError::MissingApiKey);
+        }
+
+        Ok(ApiConfig {
+            api_key,
+            base_url,
+            model: None,
+        })
+    }
+
+    /// Resolve config from saved settings, falling back to environment variables.
+    /// `OPENAI_API_KEY` is never sent to a server saved in settings, only to OpenAI or
+    /// to `OPENAI_BASE_URL`; a custom server needs its key saved alongside it.
+    pub fn from_settings(settings: &Settings) -> Result<Self> {
+        Self::resolve(settings, ApiConfig::from_env().ok())
+    }
+
+    /// Combine saved settings with the config read from the environment
+    fn resolve(settings: &Settings, env_config: Option<ApiConfig>) -> Result<Self> {
+        let base_url = settings
+            .base_url
+            .clone()
+            .or_else(|| env_config.as_ref().and_then(|c| c.base_url.clone()));
+        let api_key = settings.openai_api_key.clone().or_else(|| {
+            env_config
+                .filter(|_| settings.base_url.is_none())
+                .and_then(|c| c.api_key)
+        });
+
+        if api_key.is_none() && base_url.is_none() {
+            return Err(WhisError::MissingApiKey);
+        }
+
+        Ok(ApiConfig {
+            api_key,
+            base_url,
+            model: settings.model.clone(),
+        })
+    }
+
+    /// Whether requests go to a custom OpenAI-compatible server
+    pub fn is_custom_endpoint(&self) -> bool {
+        self.base_url.is_some()
+    }
+
+    /// Build the transcription provider for this configuration
+    pub fn provider(&self) -> Arc<dyn TranscriptionProvider> {
+        let provider = match &self.base_url {
+            Some(base_url) => OpenAIProvider::compatible(base_url, self.api_key.clone()),
+            None => OpenAIProvider::new(self.api_key.clone().unwrap_or_default()),
+        };
+
+        match &self.model {
+            Some(model) => Arc::new(provider.with_model(model)),
+            None => Arc::new(provider),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_key() -> Option<ApiConfig> {
+        Some(ApiConfig {
+            api_key: Some("sk-env".to_string()),
+            base_url: None,
+            model: None,
+        })
+    }
+
+    #[test]
+    fn test_custom_server_does_not_get_env_key() {
+        let settings = Settings {
+            base_url: Some("http://localhost:8000/v1".to_string()),
+            ..Default::default()
+        };
+        let config = ApiConfig::resolve(&settings, env_key()).unwrap();
+        assert_eq!(config.base_url.as_deref(), Some("http://localhost:8000/v1"));
+        assert_eq!(config.api_key, None);
+    }
+
+    #[test]
+    fn test_default_endpoint_uses_env_key() {
+        let config = ApiConfig::resolve(&Settings::default(), env_key()).unwrap();
+        assert_eq!(config.base_url, None);
+        assert_eq!(config.api_key.as_deref(), Some("sk-env"));
+    }
+
+    #[test]
+    fn test_saved_key_is_used() {
+        let settings = Settings {
+            openai_api_key: Some("sk-saved".to_string()),
+            ..Default::default()
+        };
+        let config = ApiConfig::resolve(&settings, env_key()).unwrap();
+        assert_eq!(config.api_key.as_deref(), Some("sk-saved"));
+
+        let settings = Settings {
+            base_url: Some("http://localhost:8000/v1".to_string()),
+            ..settings
+        };
+        let config = ApiConfig::resolve(&settings, env_key()).unwrap();
+        assert_eq!(config.api_key.as_deref(), Some("sk-saved"));
+    }
 
-        Ok(ApiConfig { openai_api_key })
+    #[test]
+    fn test_missing_key_is_an_error() {
+        assert!(matches!(
+            ApiConfig::resolve(&Settings::default(), None),
+            Err(WhisError::MissingApiKey)
+        ));
     }
 }
@@ -0,0 +1,261 @@
+//! Decoding existing audio and video files
+//!
+//! Containers and codecs are handled by symphonia: WAV, MP3, FLAC, Ogg Vorbis,
+//! and AAC or ALAC in MP4/M4A and MKV/WebM. Video files work as long as their
+//! sound is in one of these codecs; only the first audio track is decoded.
+//! Headerless PCM, as piped from `arecord` or `ffmpeg -f s16le`, is read with
+//! [`decode_raw`].
+
+use anyhow::Context;
+use std::fmt;
+use std::fs::File;
+use std::io::{Cursor, ErrorKind};
+use std::path::Path;
+use std::str::FromStr;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{CODEC_TYPE_NULL, Decoder, DecoderOptions};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::{MediaSource, MediaSourceStream};
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::audio::RecordingData;
+use crate::error::{Result, WhisError};
+
+/// Decode an audio or video file into interleaved samples at its own rate and channels
+pub fn decode_file(path: &Path) -> Result<RecordingData> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|ext| ext.to_str()) {
+        hint.with_extension(extension);
+    }
+    decode(Box::new(file), hint)
+}
+
+/// Decode an audio or video stream held in memory, such as one read from a pipe.
+/// The container is recognized from its contents.
+pub fn decode_bytes(data: Vec<u8>) -> Result<RecordingData> {
+    decode(Box::new(Cursor::new(data)), Hint::new())
+}
+
+/// Sample encoding of headerless PCM
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PcmFormat {
+    /// 16-bit signed integer, little-endian (`arecord -f S16_LE`, `ffmpeg -f s16le`)
+    #[default]
+    S16Le,
+    /// 32-bit signed integer, little-endian
+    S32Le,
+    /// 32-bit float, little-endian
+    F32Le,
+}
+
+impl PcmFormat {
+    const ALL: [Self; 3] = [Self::S16Le, Self::S32Le, Self::F32Le];
+
+    /// Bytes per sample
+    fn sample_len(self) -> usize {
+        match self {
+            Self::S16Le => 2,
+            Self::S32Le | Self::F32Le => 4,
+        }
+    }
+
+    fn to_f32(self, bytes: &[u8]) -> f32 {
+        match self {
+            Self::S16Le => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+            Self::S32Le => {
+                i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32
+                    / 2_147_483_648.0
+            }
+            Self::F32Le => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    }
+}
+
+impl fmt::Display for PcmFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::S16Le => "s16le",
+            Self::S32Le => "s32le",
+            Self::F32Le => "f32le",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for PcmFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|format| format.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown sample format '{s}' (expected s16le, s32le or f32le)"))
+    }
+}
+
+/// Decode headerless interleaved PCM. A partial frame at the end (a pipe closed
+/// mid-write) is dropped.
+pub fn decode_raw(
+    data: &[u8],
+    format: PcmFormat,
+    sample_rate: u32,
+    channels: u16,
+) -> Result<RecordingData> {
+    if sample_rate == 0 || channels == 0 {
+        return Err(WhisError::Decoding(
+            "sample rate and channels must be above zero".to_string(),
+        ));
+    }
+    let frame_len = format.sample_len() * channels as usize;
+    let whole_frames = data.len() / frame_len * frame_len;
+    let samples: Vec<f32> = data[..whole_frames]
+        .chunks_exact(format.sample_len())
+        .map(|bytes| format.to_f32(bytes))
+        .collect();
+    if samples.is_empty() {
+        return Err(WhisError::EmptyRecording);
+    }
+    Ok(RecordingData::new(samples, sample_rate, channels))
+}
+
+/// Probe the container, then decode the first audio track with a supported codec
+fn decode(source: Box<dyn MediaSource>, hint: Hint) -> Result<RecordingData> {
+    let stream = MediaSourceStream::new(source, Default::default());
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            stream,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| WhisError::Decoding(format!("unsupported or unrecognized format ({e})")))?;
+    let mut format = probed.format;
+
+    let (track_id, mut decoder) = audio_decoder(format.as_ref())?;
+    let mut samples = Vec::new();
+    let mut layout: Option<(u32, usize)> = None;
+    let mut buffer: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymphoniaError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(WhisError::Decoding(e.to_string())),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt packet only loses a few milliseconds; keep going like players do
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(WhisError::Decoding(e.to_string())),
+        };
+        let spec = *decoded.spec();
+        let current = (spec.rate, spec.channels.count());
+        match layout {
+            None => layout = Some(current),
+            Some(layout) if layout != current => {
+                return Err(WhisError::Decoding(
+                    "sample rate or channels change partway through".to_string(),
+                ));
+            }
+            Some(_) => {}
+        }
+
+        let needed = decoded.capacity() * spec.channels.count();
+        if buffer
+            .as_ref()
+            .is_none_or(|buffer| buffer.capacity() < needed)
+        {
+            buffer = Some(SampleBuffer::new(decoded.capacity() as u64, spec));
+        }
+        let buffer = buffer.as_mut().unwrap();
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend_from_slice(buffer.samples());
+    }
+
+    let Some((sample_rate, channels)) = layout else {
+        return Err(WhisError::EmptyRecording);
+    };
+    if samples.is_empty() {
+        return Err(WhisError::EmptyRecording);
+    }
+    Ok(RecordingData::new(samples, sample_rate, channels as u16))
+}
+
+/// Find the first track that has a decoder (video tracks are skipped)
+fn audio_decoder(format: &dyn FormatReader) -> Result<(u32, Box<dyn Decoder>)> {
+    let codecs = symphonia::default::get_codecs();
+    format
+        .tracks()
+        .iter()
+        .filter(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .find_map(|track| {
+            codecs
+                .make(&track.codec_params, &DecoderOptions::default())
+                .ok()
+                .map(|decoder| (track.id, decoder))
+        })
+        .ok_or_else(|| WhisError::Decoding("no audio track with a supported codec".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_raw_s16le() {
+        let samples: [i16; 4] = [0, 16384, -16384, i16::MIN];
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let (decoded, sample_rate, channels) = decode_raw(&data, PcmFormat::S16Le, 16000, 2)
+            .unwrap()
+            .into_parts();
+        assert_eq!((sample_rate, channels), (16000, 2));
+        assert_eq!(decoded, [0.0, 0.5, -0.5, -1.0]);
+    }
+
+    #[test]
+    fn test_decode_raw_f32le() {
+        let samples = [0.25f32, -0.75, 1.0];
+        let data: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let (decoded, sample_rate, channels) = decode_raw(&data, PcmFormat::F32Le, 48000, 1)
+            .unwrap()
+            .into_parts();
+        assert_eq!((sample_rate, channels), (48000, 1));
+        assert_eq!(decoded, samples);
+    }
+
+    #[test]
+    fn test_decode_raw_drops_partial_frame() {
+        // Two stereo s16le frames, then half a sample of a third
+        let data = [0u8; 2 * 2 * 2 + 1];
+        let (decoded, _, _) = decode_raw(&data, PcmFormat::S16Le, 16000, 2)
+            .unwrap()
+            .into_parts();
+        assert_eq!(decoded.len(), 4);
+
+        // Less than a frame is nothing at all
+        assert!(matches!(
+            decode_raw(&[0u8; 3], PcmFormat::S16Le, 16000, 2),
+            Err(WhisError::EmptyRecording)
+        ));
+    }
+
+    #[test]
+    fn test_decode_raw_rejects_empty_format() {
+        let data = [0u8; 16];
+        assert!(matches!(
+            decode_raw(&data, PcmFormat::S16Le, 16000, 0),
+            Err(WhisError::Decoding(_))
+        ));
+        assert!(matches!(
+            decode_raw(&data, PcmFormat::F32Le, 0, 1),
+            Err(WhisError::Decoding(_))
+        ));
+    }
+}
@@ -0,0 +1,247 @@
+//! Signal processing applied to recordings before they are encoded
+
+use rubato::{FftFixedIn, Resampler};
+use std::ops::Range;
+
+use crate::error::{Result, WhisError};
+
+/// Input frames fed to the resampler per call
+const RESAMPLE_CHUNK_FRAMES: usize = 1024;
+/// Length of the windows compared when looking for a pause
+const PAUSE_WINDOW_MS: usize = 200;
+
+/// Average interleaved channels into a single mono channel
+pub fn downmix(samples: &[f32], channels: u16) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    if channels == 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect()
+}
+
+/// Resample mono audio with a band-limited (FFT) resampler.
+/// The output is aligned with the input: the resampler's delay is trimmed off.
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>> {
+    if from_rate == to_rate || samples.is_empty() {
+        return Ok(samples.to_vec());
+    }
+
+    let mut resampler = StreamResampler::new(from_rate, to_rate)?;
+    let mut output = resampler.process(samples)?;
+    output.extend(resampler.finish()?);
+    Ok(output)
+}
+
+/// Band-limited resampler for mono audio that arrives in pieces, e.g. while recording.
+/// The filter state carries over between pieces, so they join without clicks, and
+/// the output of all [`process`](Self::process) calls followed by
+/// [`finish`](Self::finish) matches [`resample`] of the whole input.
+pub struct StreamResampler {
+    /// `None` when the rates match and samples pass through
+    resampler: Option<FftFixedIn<f32>>,
+    from_rate: u32,
+    to_rate: u32,
+    /// Input held back until it fills a whole chunk
+    pending: Vec<f32>,
+    /// Output frames still to drop for the resampler's delay
+    delay: usize,
+    input_len: u64,
+    output_len: u64,
+}
+
+impl StreamResampler {
+    pub fn new(from_rate: u32, to_rate: u32) -> Result<Self> {
+        let resampler = if from_rate == to_rate {
+            None
+        } else {
+            let resampler = FftFixedIn::<f32>::new(
+                from_rate as usize,
+                to_rate as usize,
+                RESAMPLE_CHUNK_FRAMES,
+                2,
+                1,
+            )
+            .map_err(resample_error)?;
+            Some(resampler)
+        };
+        Ok(Self {
+            delay: resampler.as_ref().map_or(0, |r| r.output_delay()),
+            resampler,
+            from_rate,
+            to_rate,
+            pending: Vec::with_capacity(RESAMPLE_CHUNK_FRAMES),
+            input_len: 0,
+            output_len: 0,
+        })
+    }
+
+    /// Feed the next piece of audio, returning the output that is ready so far
+    pub fn process(&mut self, samples: &[f32]) -> Result<Vec<f32>> {
+        self.input_len += samples.len() as u64;
+        let Some(resampler) = &mut self.resampler else {
+            return Ok(samples.to_vec());
+        };
+
+        self.pending.extend_from_slice(samples);
+        let mut output = Vec::new();
+        let mut chunks = self.pending.chunks_exact(RESAMPLE_CHUNK_FRAMES);
+        for chunk in chunks.by_ref() {
+            let out = resampler.process(&[chunk], None).map_err(resample_error)?;
+            output.extend_from_slice(&out[0]);
+        }
+        let consumed = self.pending.len() - chunks.remainder().len();
+        self.pending.drain(..consumed);
+        Ok(self.emit(output))
+    }
+
+    /// Resample the input still held back and flush the resampler's delay
+    pub fn finish(mut self) -> Result<Vec<f32>> {
+        let expected_len = (self.input_len * self.to_rate as u64).div_ceil(self.from_rate as u64);
+        let remaining = expected_len.saturating_sub(self.output_len) as usize;
+        let mut output = Vec::new();
+        let Some(resampler) = &mut self.resampler else {
+            return Ok(output);
+        };
+
+        if !self.pending.is_empty() {
+            let out = resampler
+                .process_partial(Some(&[self.pending.as_slice()]), None)
+                .map_err(resample_error)?;
+            output.extend_from_slice(&out[0]);
+        }
+        // Push out the frames still held back by the resampler's delay
+        while output.len() < remaining + self.delay {
+            let out = resampler
+                .process_partial(None::<&[&[f32]]>, None)
+                .map_err(resample_error)?;
+            if out[0].is_empty() {
+                break;
+            }
+            output.extend_from_slice(&out[0]);
+        }
+
+        let mut output = self.emit(output);
+        output.truncate(remaining);
+        Ok(output)
+    }
+
+    /// Drop what's left of the delay from freshly resampled output
+    fn emit(&mut self, mut output: Vec<f32>) -> Vec<f32> {
+        let skip = self.delay.min(output.len());
+        output.drain(..skip);
+        self.delay -= skip;
+        self.output_len += output.len() as u64;
+        output
+    }
+}
+
+/// Frame in `frames` at the middle of the quietest short window of interleaved `samples`,
+/// i.e. the best place to cut without splitting a word
+pub fn quietest_frame(
+    samples: &[f32],
+    channels: u16,
+    sample_rate: u32,
+    frames: Range<usize>,
+) -> usize {
+    let channels = channels.max(1) as usize;
+    let window = (sample_rate as usize * PAUSE_WINDOW_MS / 1000).max(1);
+    if frames.len() <= window {
+        return frames.start + frames.len() / 2;
+    }
+
+    // Slide by half a window; the first of equally quiet windows wins
+    let mut quietest = (f32::MAX, frames.start);
+    let mut start = frames.start;
+    while start + window <= frames.end {
+        let frame_samples = &samples[start * channels..(start + window) * channels];
+        let energy = frame_samples.iter().map(|s| s * s).sum::<f32>();
+        if energy < quietest.0 {
+            quietest = (energy, start);
+        }
+        start += window / 2;
+    }
+    quietest.1 + window / 2
+}
+
+fn resample_error(err: impl std::fmt::Display) -> WhisError {
+    WhisError::Encoding(format!("Failed to resample audio: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 1000;
+
+    /// Loud audio with a silent gap over `gap` (in frames)
+    fn with_gap(len: usize, gap: Range<usize>, channels: u16) -> Vec<f32> {
+        (0..len)
+            .flat_map(|frame| {
+                let sample = if gap.contains(&frame) { 0.0 } else { 0.5 };
+                std::iter::repeat_n(sample, channels as usize)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_quietest_frame_finds_the_gap() {
+        let samples = with_gap(5000, 2600..3000, 1);
+        let frame = quietest_frame(&samples, 1, RATE, 1000..4000);
+        assert!((2600..3000).contains(&frame), "cut at {frame}");
+    }
+
+    #[test]
+    fn test_quietest_frame_handles_interleaved_channels() {
+        let samples = with_gap(5000, 1200..1600, 2);
+        let frame = quietest_frame(&samples, 2, RATE, 1000..4000);
+        assert!((1200..1600).contains(&frame), "cut at {frame}");
+    }
+
+    #[test]
+    fn test_quietest_frame_prefers_the_first_of_equal_windows() {
+        let samples = vec![0.0; 5000];
+        assert_eq!(quietest_frame(&samples, 1, RATE, 1000..4000), 1100);
+    }
+
+    #[test]
+    fn test_quietest_frame_in_short_range_is_the_middle() {
+        let samples = vec![0.5; 1000];
+        assert_eq!(quietest_frame(&samples, 1, RATE, 100..200), 150);
+    }
+
+    fn sine(len: usize, rate: u32) -> Vec<f32> {
+        (0..len)
+            .map(|i| (i as f32 * 440.0 * std::f32::consts::TAU / rate as f32).sin() * 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn test_resample_keeps_duration() {
+        let output = resample(&sine(48000, 48000), 48000, 24000).unwrap();
+        assert_eq!(output.len(), 24000);
+        let output = resample(&sine(44100 + 7, 44100), 44100, 16000).unwrap();
+        assert_eq!(output.len(), (44107u64 * 16000).div_ceil(44100) as usize);
+    }
+
+    #[test]
+    fn test_stream_resampler_matches_whole_input() {
+        let input = sine(48000 + 333, 48000);
+        let whole = resample(&input, 48000, 24000).unwrap();
+
+        let mut resampler = StreamResampler::new(48000, 24000).unwrap();
+        let mut streamed = Vec::new();
+        // Pieces that don't line up with the resampler's chunks
+        for piece in input.chunks(4800 + 17) {
+            streamed.extend(resampler.process(piece).unwrap());
+        }
+        streamed.extend(resampler.finish().unwrap());
+
+        assert_eq!(streamed.len(), whole.len());
+        for (a, b) in streamed.iter().zip(&whole) {
+            assert!((a - b).abs() < 1e-5);
+        }
+    }
+}
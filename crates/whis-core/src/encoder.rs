@@ -0,0 +1,408 @@
+//! Audio encoding for upload
+//!
+//! Recordings are compressed before they are sent to a provider. MP3 (bundled
+//! LAME) and FLAC (pure Rust) are encoded in-process, so no external binaries are
+//! needed; the FFmpeg encoder is kept as a fallback for setups that prefer it.
+//!
+//! The [`EncodingProfile`] decides what is encoded: by default recordings are
+//! downmixed to mono and resampled to 16 kHz, the rate Whisper works at, which
+//! keeps uploads small without costing accuracy.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::dsp;
+use crate::error::{Result, WhisError};
+use crate::preprocess::Preprocessing;
+use crate::settings::Settings;
+use crate::vad::VadAggressiveness;
+
+/// Encoder used to compress recordings before upload
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioEncoder {
+    /// MP3 via the bundled LAME library
+    #[default]
+    Mp3,
+    /// Lossless FLAC, pure Rust (larger uploads than MP3)
+    Flac,
+    /// MP3 via the external `ffmpeg` binary
+    Ffmpeg,
+}
+
+impl AudioEncoder {
+    pub const ALL: [Self; 3] = [Self::Mp3, Self::Flac, Self::Ffmpeg];
+
+    /// File extension of the encoded audio, used in upload file names
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Mp3 | Self::Ffmpeg => "mp3",
+            Self::Flac => "flac",
+        }
+    }
+
+    /// Check that the encoder can run (only FFmpeg depends on anything external)
+    pub fn ensure_available(self) -> Result<()> {
+        match self {
+            Self::Ffmpeg => ensure_ffmpeg(),
+            Self::Mp3 | Self::Flac => Ok(()),
+        }
+    }
+
+    /// Encode interleaved f32 samples (`bitrate_kbps` is ignored by lossless FLAC)
+    pub fn encode(
+        self,
+        samples: &[f32],
+        sample_rate: u32,
+        channels: u16,
+        bitrate_kbps: u32,
+    ) -> Result<Vec<u8>> {
+        match self {
+            Self::Mp3 => encode_mp3(samples, sample_rate, channels, bitrate_kbps),
+            Self::Flac => encode_flac(samples, sample_rate, channels),
+            Self::Ffmpeg => encode_ffmpeg(samples, sample_rate, channels, bitrate_kbps),
+        }
+    }
+}
+
+/// MIME type for an audio file extension, `application/octet-stream` if unknown
+pub fn mime_type(extension: &str) -> &'static str {
+    match extension.to_ascii_lowercase().as_str() {
+        "mp3" => "audio/mpeg",
+        "flac" => "audio/flac",
+        "ogg" | "oga" | "opus" => "audio/ogg",
+        "wav" => "audio/wav",
+        "webm" => "audio/webm",
+        "m4a" | "mp4" => "audio/mp4",
+        _ => "application/octet-stream",
+    }
+}
+
+impl fmt::Display for AudioEncoder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Mp3 => "mp3",
+            Self::Flac => "flac",
+            Self::Ffmpeg => "ffmpeg",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for AudioEncoder {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|encoder| encoder.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown encoder '{s}' (expected mp3, flac or ffmpeg)"))
+    }
+}
+
+/// Sample rate, channel layout and bitrate recordings are encoded with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EncodingProfile {
+    /// 16 kHz mono at 32 kbps, the rate Whisper models work at
+    #[default]
+    Speech,
+    /// 24 kHz mono at 64 kbps, for noisy rooms or models that use the extra bandwidth
+    Balanced,
+    /// The device's own rate and channels at 128 kbps
+    Original,
+}
+
+impl EncodingProfile {
+    pub const ALL: [Self; 3] = [Self::Speech, Self::Balanced, Self::Original];
+
+    /// Sample rate to resample to, or `None` to keep the device's rate and channels
+    pub fn sample_rate(self) -> Option<u32> {
+        match self {
+            Self::Speech => Some(16000),
+            Self::Balanced => Some(24000),
+            Self::Original => None,
+        }
+    }
+
+    /// MP3 bitrate in kbps
+    pub fn bitrate_kbps(self) -> u32 {
+        match self {
+            Self::Speech => 32,
+            Self::Balanced => 64,
+            Self::Original => 128,
+        }
+    }
+}
+
+impl fmt::Display for EncodingProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Speech => "speech",
+            Self::Balanced => "balanced",
+            Self::Original => "original",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for EncodingProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|profile| profile.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                format!("unknown encoding profile '{s}' (expected speech, balanced or original)")
+            })
+    }
+}
+
+/// How recordings are prepared and compressed before upload
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EncodingOptions {
+    pub encoder: AudioEncoder,
+    pub profile: EncodingProfile,
+    /// Silence trimming applied with [`RecordingData::trim_silence`](crate::RecordingData::trim_silence)
+    pub vad: VadAggressiveness,
+    /// Clean-up applied with [`RecordingData::preprocess`](crate::RecordingData::preprocess)
+    pub preprocess: Preprocessing,
+}
+
+impl EncodingOptions {
+    /// Encoding options saved in settings
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            encoder: settings.encoder,
+            profile: settings.encoding_profile,
+            vad: settings.vad,
+            preprocess: Preprocessing::from_settings(settings),
+        }
+    }
+}
+
+fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+}
+
+fn encode_mp3(
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+    bitrate_kbps: u32,
+) -> Result<Vec<u8>> {
+    use mp3lame_encoder::{Builder, FlushNoGap, InterleavedPcm, MonoPcm};
+
+    // LAME takes mono or stereo; fold anything wider down to mono
+    let (samples, channels) = if channels > 2 {
+        (dsp::downmix(samples, channels), 1)
+    } else {
+        (samples.to_vec(), channels.max(1))
+    };
+    let pcm: Vec<i16> = samples.iter().map(|&s| to_i16(s)).collect();
+
+    let mut builder =
+        Builder::new().ok_or_else(|| encoding_error("Failed to create MP3 encoder"))?;
+    builder
+        .set_num_channels(channels as u8)
+        .map_err(encoding_error)?;
+    builder
+        .set_sample_rate(sample_rate)
+        .map_err(encoding_error)?;
+    builder
+        .set_brate(mp3_bitrate(bitrate_kbps))
+        .map_err(encoding_error)?;
+    builder
+        .set_quality(mp3lame_encoder::Quality::Good)
+        .map_err(encoding_error)?;
+    let mut encoder = builder.build().map_err(encoding_error)?;
+
+    let mut mp3 = Vec::with_capacity(mp3lame_encoder::max_required_buffer_size(pcm.len()));
+    if channels == 1 {
+        encoder.encode_to_vec(MonoPcm(pcm.as_slice()), &mut mp3)
+    } else {
+        encoder.encode_to_vec(InterleavedPcm(pcm.as_slice()), &mut mp3)
+    }
+    .map_err(encoding_error)?;
+    encoder
+        .flush_to_vec::<FlushNoGap>(&mut mp3)
+        .map_err(encoding_error)?;
+    Ok(mp3)
+}
+
+/// Closest LAME bitrate at or below `kbps`
+fn mp3_bitrate(kbps: u32) -> mp3lame_encoder::Bitrate {
+    use mp3lame_encoder::Bitrate::*;
+    [
+        (320, Kbps320),
+        (256, Kbps256),
+        (224, Kbps224),
+        (192, Kbps192),
+        (160, Kbps160),
+        (128, Kbps128),
+        (112, Kbps112),
+        (96, Kbps96),
+        (80, Kbps80),
+        (64, Kbps64),
+        (48, Kbps48),
+        (40, Kbps40),
+        (32, Kbps32),
+        (24, Kbps24),
+        (16, Kbps16),
+    ]
+    .into_iter()
+    .find(|&(rate, _)| rate <= kbps)
+    .map_or(Kbps8, |(_, bitrate)| bitrate)
+}
+
+fn encode_flac(samples: &[f32], sample_rate: u32, channels: u16) -> Result<Vec<u8>> {
+    use flacenc::component::BitRepr;
+    use flacenc::error::Verify;
+
+    let pcm: Vec<i32> = samples.iter().map(|&s| to_i16(s) as i32).collect();
+    let config = flacenc::config::Encoder::default()
+        .into_verified()
+        .map_err(|(_, e)| encoding_error(format!("{e:?}")))?;
+    let source = flacenc::source::MemSource::from_samples(
+        &pcm,
+        channels.max(1) as usize,
+        16,
+        sample_rate as usize,
+    );
+    let mut stream = flacenc::encode_with_fixed_block_size(&config, source, config.block_size)
+        .map_err(|e| encoding_error(format!("{e:?}")))?;
+
+    // flacenc counts the shorter last block in the minimum block size, which makes
+    // the stream look variable-sized and decoders like symphonia reject its frames.
+    // The minimum excludes the last block, so with fixed-size blocks it is the maximum.
+    let info = stream.stream_info_mut();
+    let block_size = info.max_block_size();
+    if info.min_block_size() < block_size {
+        info.set_block_sizes(block_size, block_size)
+            .map_err(|e| encoding_error(format!("{e:?}")))?;
+    }
+
+    let mut sink = flacenc::bitsink::ByteSink::new();
+    stream
+        .write(&mut sink)
+        .map_err(|e| encoding_error(format!("{e:?}")))?;
+    Ok(sink.into_inner())
+}
+
+/// Write a temporary WAV file and convert it to MP3 with FFmpeg
+fn encode_ffmpeg(
+    samples: &[f32],
+    sample_rate: u32,
+    channels: u16,
+    bitrate_kbps: u32,
+) -> Result<Vec<u8>> {
+    // Use unique temp file names to support parallel FFmpeg calls
+    static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let temp_dir = std::env::temp_dir();
+    let unique_id = format!(
+        "{}_{}_{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos(),
+        COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+    );
+    let wav_path = temp_dir.join(format!("whis_{unique_id}.wav"));
+    let mp3_path = temp_dir.join(format!("whis_{unique_id}.mp3"));
+
+    {
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+
+        let mut writer = hound::WavWriter::create(&wav_path, spec).map_err(encoding_error)?;
+        for &sample in samples {
+            writer
+                .write_sample(to_i16(sample))
+                .map_err(encoding_error)?;
+        }
+        writer.finalize().map_err(encoding_error)?;
+    }
+
+    // Convert WAV to MP3 using FFmpeg
+    let output = std::process::Command::new("ffmpeg")
+        .args([
+            "-hide_banner",
+            "-loglevel",
+            "error",
+            "-i",
+            wav_path.to_str().unwrap(),
+            "-codec:a",
+            "libmp3lame",
+            "-b:a",
+            &format!("{bitrate_kbps}k"),
+            "-y",
+            mp3_path.to_str().unwrap(),
+        ])
+        .output()
+        .map_err(ffmpeg_error);
+
+    // Clean up the temporary WAV file
+    let _ = std::fs::remove_file(&wav_path);
+    let output = output?;
+
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&mp3_path);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(WhisError::Encoding(format!(
+            "FFmpeg conversion failed: {stderr}"
+        )));
+    }
+
+    // Read the MP3 file
+    let mp3_data = std::fs::read(&mp3_path).map_err(encoding_error);
+
+    // Clean up the temporary MP3 file
+    let _ = std::fs::remove_file(&mp3_path);
+
+    mp3_data
+}
+
+/// Check that FFmpeg can be run (needed by the FFmpeg encoder)
+pub fn ensure_ffmpeg() -> Result<()> {
+    std::process::Command::new("ffmpeg")
+        .arg("-version")
+        .output()
+        .map_err(ffmpeg_error)?;
+    Ok(())
+}
+
+/// Map a failure to start FFmpeg, telling a missing binary apart from other errors
+fn ffmpeg_error(err: std::io::Error) -> WhisError {
+    if err.kind() == std::io::ErrorKind::NotFound {
+        WhisError::FfmpegMissing
+    } else {
+        WhisError::Encoding(format!("Failed to execute ffmpeg: {err}"))
+    }
+}
+
+fn encoding_error(err: impl fmt::Display) -> WhisError {
+    WhisError::Encoding(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::decode_bytes;
+
+    #[test]
+    fn test_flac_decodes_when_last_block_is_short() {
+        // Not a multiple of the block size
+        let samples: Vec<f32> = (0..16000).map(|i| (i as f32 * 0.05).sin() * 0.3).collect();
+        let data = encode_flac(&samples, 16000, 1).unwrap();
+        let (decoded, sample_rate, channels) = decode_bytes(data).unwrap().into_parts();
+        assert_eq!((sample_rate, channels), (16000, 1));
+        assert_eq!(decoded.len(), samples.len());
+    }
+}
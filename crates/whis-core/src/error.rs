@@ -0,0 +1,160 @@
+//! Error type for the public whis-core API
+//!
+//! Frontends match on [`WhisError`] to show recovery hints (install FFmpeg,
+//! check the API key, ...). Anything without a dedicated variant ends up in
+//! [`WhisError::Other`].
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Result type used throughout the whis-core API
+pub type Result<T, E = WhisError> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum WhisError {
+    /// No microphone or other input device found
+    #[error("No input device available")]
+    NoInputDevice,
+
+    /// The input device exists but could not be opened or streamed from
+    #[error("Audio input error: {0}")]
+    AudioInput(String),
+
+    /// Recording stopped before any samples arrived
+    #[error("No audio data recorded")]
+    EmptyRecording,
+
+    /// Pausing or resuming while no recording is in progress
+    #[error("Not recording")]
+    NotRecording,
+
+    /// FFmpeg is needed for MP3 encoding but isn't on PATH
+    #[error("FFmpeg is not installed or not in PATH")]
+    FfmpegMissing,
+
+    /// Encoding the recording failed
+    #[error("Audio encoding failed: {0}")]
+    Encoding(String),
+
+    /// An audio or video file could not be read (unknown format, unsupported codec)
+    #[error("Could not decode audio: {0}")]
+    Decoding(String),
+
+    /// A transcript could not be serialized or written to its output file
+    #[error("Could not write transcript: {0}")]
+    Output(String),
+
+    /// Neither an API key nor a self-hosted server is configured
+    #[error("No API key configured")]
+    MissingApiKey,
+
+    /// The provider rejected the API key (401/403)
+    #[error("{provider} rejected the API key: {message}")]
+    Authentication {
+        provider: &'static str,
+        message: String,
+    },
+
+    /// Too many requests (429)
+    #[error("{provider} rate limit reached: {message}")]
+    RateLimited {
+        provider: &'static str,
+        message: String,
+        /// Delay requested by the server via `Retry-After`
+        retry_after: Option<Duration>,
+    },
+
+    /// The account is out of credits or over its spending limit (429 `insufficient_quota`);
+    /// retrying won't help until billing is sorted out
+    #[error("{provider} quota exceeded: {message}")]
+    QuotaExceeded {
+        provider: &'static str,
+        message: String,
+    },
+
+    /// The request didn't complete within the timeout
+    #[error("Request to {provider} timed out")]
+    Timeout { provider: &'static str },
+
+    /// The provider could not be reached (DNS, refused connection, dropped connection)
+    #[error("Could not reach {provider}: {message}")]
+    Network {
+        provider: &'static str,
+        message: String,
+    },
+
+    /// Any other non-success HTTP status
+    #[error("{provider} API error ({status}): {message}")]
+    Api {
+        provider: &'static str,
+        status: u16,
+        message: String,
+        /// Delay requested by the server via `Retry-After` (e.g. with a 503)
+        retry_after: Option<Duration>,
+    },
+
+    /// The provider answered with something we couldn't parse
+    #[error("Unexpected response from {provider}: {message}")]
+    BadResponse {
+        provider: &'static str,
+        message: String,
+    },
+
+    /// A local model is selected but its file is missing
+    #[error("Model '{0}' is not downloaded")]
+    ModelNotDownloaded(String),
+
+    /// A local model is selected but whis was built without the `local-whisper` feature
+    #[error(
+        "Local model '{0}' is selected, but whis was built without the `local-whisper` feature"
+    )]
+    LocalWhisperUnavailable(String),
+
+    /// The caller cancelled the transcription
+    #[error("Transcription cancelled")]
+    Cancelled,
+
+    /// A recording could not be transcribed, even after retrying. Its audio was
+    /// saved to `dir` so it can be transcribed later.
+    #[error("{source} (recording saved to {})", dir.display())]
+    RecordingSaved {
+        source: Box<WhisError>,
+        dir: PathBuf,
+    },
+
+    /// The system clipboard could not be accessed
+    #[error("Clipboard unavailable: {0}")]
+    ClipboardUnavailable(String),
+
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl WhisError {
+    /// Whether sending the same request again may succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimited { .. } | Self::Timeout { .. } | Self::Network { .. } => true,
+            Self::Api { status, .. } => *status == 408 || *status >= 500,
+            _ => false,
+        }
+    }
+
+    /// Delay requested by the server before retrying
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            Self::RateLimited { retry_after, .. } | Self::Api { retry_after, .. } => *retry_after,
+            _ => None,
+        }
+    }
+}
+
+impl From<anyhow::Error> for WhisError {
+    /// Keep typed errors that were passed through `anyhow` intact
+    fn from(err: anyhow::Error) -> Self {
+        match err.downcast::<WhisError>() {
+            Ok(err) => err,
+            Err(err) => Self::Other(err),
+        }
+    }
+}
@@ -0,0 +1,332 @@
+//! Transcription while recording
+//!
+//! Long recordings are cut into chunks at pauses as the audio comes in. Each chunk
+//! is trimmed, encoded and sent off in the background while recording continues,
+//! so stopping only has to wait for the last one and the recording never sits in
+//! memory as a whole.
+//!
+//! The encoded chunks are kept until the transcript is merged. Chunks that failed
+//! while recording are sent once more when it ends; if they still fail, the
+//! recording is saved so it isn't lost.
+
+use anyhow::Context;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
+
+use crate::audio::{
+    AudioChunk, CHUNK_DURATION_SECS, CHUNK_SPLIT_WINDOW_SECS, CHUNK_THRESHOLD_BYTES,
+    MIN_CHUNK_DURATION_SECS, RecordingData,
+};
+use crate::dsp;
+use crate::encoder::{AudioEncoder, EncodingOptions};
+use crate::error::{Result, WhisError};
+use crate::provider::{TranscriptionOptions, TranscriptionProvider};
+use crate::transcribe::{
+    ChunkResults, ProgressCallback, chunks_error, merge_transcriptions, transcribe,
+    transcribe_chunks_partial,
+};
+use crate::transcript::Transcript;
+use crate::vad::TimeMap;
+
+/// Transcribe interleaved samples from `audio` (see `AudioRecorder::stream_samples`)
+/// chunk by chunk while they are recorded. Resolves once the channel closes and the
+/// last chunk is transcribed.
+///
+/// If some chunks can't be transcribed, the recording is saved and
+/// [`WhisError::RecordingSaved`] tells where.
+///
+/// Local models read raw PCM, so for them the audio is collected and transcribed in
+/// one go once recording ends.
+#[allow(clippy::too_many_arguments)]
+pub async fn transcribe_while_recording(
+    provider: Arc<dyn TranscriptionProvider>,
+    mut audio: mpsc::UnboundedReceiver<Vec<f32>>,
+    sample_rate: u32,
+    channels: u16,
+    options: &TranscriptionOptions,
+    encoding: EncodingOptions,
+    progress_callback: Option<ProgressCallback>,
+    cancel: &CancellationToken,
+) -> Result<Transcript> {
+    if provider.accepts_pcm() {
+        let mut samples = Vec::new();
+        while let Some(buffer) = audio.recv().await {
+            samples.extend_from_slice(&buffer);
+        }
+        if samples.is_empty() {
+            return Err(WhisError::EmptyRecording);
+        }
+        let (output, time_map) = tokio::task::spawn_blocking(move || {
+            let (recording, time_map) = RecordingData::new(samples, sample_rate, channels)
+                .preprocess(&encoding.preprocess)?
+                .trim_silence(encoding.vad);
+            Ok::<_, WhisError>((recording.finalize_pcm()?, time_map))
+        })
+        .await
+        .context("Failed to join task")??;
+        let mut transcript =
+            transcribe(provider, output, options, progress_callback, cancel).await?;
+        time_map.restore(&mut transcript);
+        return Ok(transcript);
+    }
+
+    let (chunk_tx, chunk_rx) = mpsc::unbounded_channel();
+    let chunker = async move {
+        let mut chunker = Chunker {
+            sample_rate,
+            channels,
+            encoding,
+            chunks: chunk_tx,
+            sent: Vec::new(),
+            time_maps: Vec::new(),
+            offset_frames: 0,
+        };
+        let frame_len = channels.max(1) as usize;
+        let longest_secs = max_chunk_secs(&encoding, sample_rate, channels);
+        let shortest_secs = longest_secs - (2 * CHUNK_SPLIT_WINDOW_SECS).min(longest_secs / 2);
+        let longest = longest_secs * sample_rate as usize;
+        let shortest = shortest_secs * sample_rate as usize;
+
+        let mut buffer = Vec::new();
+        loop {
+            let samples = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => return Err(WhisError::Cancelled),
+                samples = audio.recv() => samples,
+            };
+            let Some(samples) = samples else {
+                break;
+            };
+            buffer.extend_from_slice(&samples);
+
+            // Cut at a pause once a full chunk has come in, so no word is split
+            while buffer.len() / frame_len >= longest {
+                let cut = dsp::quietest_frame(&buffer, channels, sample_rate, shortest..longest);
+                let chunk: Vec<f32> = buffer.drain(..cut * frame_len).collect();
+                chunker.send(chunk).await?;
+            }
+        }
+        if !buffer.is_empty() {
+            chunker.send(buffer).await?;
+        }
+        // Dropping the chunker closes the channel, so the transcriber finishes
+        Ok((chunker.sent, chunker.time_maps))
+    };
+
+    let progress_callback = progress_callback.map(Arc::new);
+    let (chunked, results) = tokio::join!(
+        chunker,
+        transcribe_chunks_partial(
+            provider.clone(),
+            chunk_rx,
+            options,
+            progress_callback.clone(),
+            cancel
+        )
+    );
+    let ((sent, time_maps), results) = (chunked?, results?);
+    if time_maps.is_empty() {
+        return Err(WhisError::EmptyRecording);
+    }
+    let ChunkResults {
+        mut transcribed,
+        failed,
+    } = results;
+
+    // Give chunks that hit a passing problem (network, server) one more chance
+    let (retry, mut failed_for_good): (Vec<_>, Vec<_>) =
+        failed.into_iter().partition(|(_, e)| e.is_retryable());
+    if !retry.is_empty() {
+        let (retry_tx, retry_rx) = mpsc::unbounded_channel();
+        for (index, _) in &retry {
+            let _ = retry_tx.send(sent[*index].clone());
+        }
+        drop(retry_tx);
+        let retried =
+            transcribe_chunks_partial(provider, retry_rx, options, progress_callback, cancel)
+                .await?;
+        transcribed.extend(retried.transcribed);
+        transcribed.sort_by_key(|r| r.index);
+        failed_for_good.extend(retried.failed);
+    }
+    if !failed_for_good.is_empty() {
+        failed_for_good.sort_by_key(|(index, _)| *index);
+        return Err(save_recording(
+            &sent,
+            chunks_error(failed_for_good, sent.len()),
+        ));
+    }
+
+    // Timestamps refer to the trimmed chunk; put them back on the chunk's own timeline
+    for result in &mut transcribed {
+        time_maps[result.index].restore(&mut result.transcript);
+    }
+    Ok(merge_transcriptions(transcribed))
+}
+
+/// Save the encoded chunks of a recording that couldn't be transcribed, returning
+/// `error` with where they went (or as is if saving failed too)
+fn save_recording(chunks: &[AudioChunk], error: WhisError) -> WhisError {
+    match write_chunks(chunks) {
+        Ok(dir) => WhisError::RecordingSaved {
+            source: Box::new(error),
+            dir,
+        },
+        Err(_) => error,
+    }
+}
+
+/// Write chunks to a new directory under the data dir, in recording order
+fn write_chunks(chunks: &[AudioChunk]) -> std::io::Result<PathBuf> {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let dir = dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("whis")
+        .join("unsent")
+        .join(secs.to_string());
+    std::fs::create_dir_all(&dir)?;
+    for chunk in chunks {
+        let name = format!("chunk-{:03}.{}", chunk.index + 1, chunk.extension);
+        std::fs::write(dir.join(name), &chunk.data)?;
+    }
+    Ok(dir)
+}
+
+/// Turns finished stretches of audio into chunks for upload
+struct Chunker {
+    sample_rate: u32,
+    channels: u16,
+    encoding: EncodingOptions,
+    chunks: mpsc::UnboundedSender<AudioChunk>,
+    /// Every chunk sent so far, kept until the transcript is merged
+    sent: Vec<AudioChunk>,
+    /// Silence trimming map of each chunk sent so far
+    time_maps: Vec<TimeMap>,
+    /// Start of the next chunk in the recording
+    offset_frames: usize,
+}
+
+impl Chunker {
+    /// Clean up, trim and encode `samples` off the async runtime, then queue them for transcription
+    async fn send(&mut self, samples: Vec<f32>) -> Result<()> {
+        let index = self.time_maps.len();
+        let offset_secs = self.offset_frames as f64 / self.sample_rate as f64;
+        self.offset_frames += samples.len() / self.channels.max(1) as usize;
+
+        let (sample_rate, channels, encoding) = (self.sample_rate, self.channels, self.encoding);
+        let (data, time_map) = tokio::task::spawn_blocking(move || {
+            let (recording, time_map) = RecordingData::new(samples, sample_rate, channels)
+                .preprocess(&encoding.preprocess)?
+                .trim_silence(encoding.vad);
+            Ok::<_, WhisError>((recording.encode_whole(&encoding)?, time_map))
+        })
+        .await
+        .context("Failed to join task")??;
+
+        self.time_maps.push(time_map);
+        let chunk = AudioChunk {
+            data,
+            extension: self.encoding.encoder.extension(),
+            index,
+            offset_secs,
+        };
+        self.sent.push(chunk.clone());
+        // The transcriber only goes away when it has failed or was cancelled
+        let _ = self.chunks.send(chunk);
+        Ok(())
+    }
+}
+
+/// Longest chunk, in seconds, that is sure to encode under the upload limit
+fn max_chunk_secs(encoding: &EncodingOptions, sample_rate: u32, channels: u16) -> usize {
+    let (rate, channels) = match encoding.profile.sample_rate() {
+        Some(rate) => (rate, 1),
+        None => (sample_rate, channels.max(1)),
+    };
+    let bytes_per_sec = match encoding.encoder {
+        // FLAC never gets much bigger than the 16-bit PCM it compresses
+        AudioEncoder::Flac => rate as usize * channels as usize * 2,
+        AudioEncoder::Mp3 | AudioEncoder::Ffmpeg => {
+            encoding.profile.bitrate_kbps() as usize * 1000 / 8
+        }
+    };
+    (CHUNK_THRESHOLD_BYTES * 9 / 10 / bytes_per_sec.max(1))
+        .clamp(MIN_CHUNK_DURATION_SECS, CHUNK_DURATION_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::TranscriptionRequest;
+    use crate::retry::RetryPolicy;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    /// Fails the first `failures` requests with a server error, then succeeds
+    struct FlakyProvider {
+        failures: usize,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl TranscriptionProvider for FlakyProvider {
+        fn name(&self) -> &'static str {
+            "flaky"
+        }
+
+        async fn transcribe(
+            &self,
+            _client: &reqwest::Client,
+            _request: TranscriptionRequest,
+        ) -> Result<Transcript> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(WhisError::Api {
+                    provider: "flaky",
+                    status: 503,
+                    message: "busy".to_string(),
+                    retry_after: Some(Duration::ZERO),
+                });
+            }
+            Ok(Transcript {
+                text: "hello".to_string(),
+                ..Default::default()
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_chunk_is_sent_again_after_recording() {
+        // Every attempt while recording fails; the one after it succeeds
+        let failures = RetryPolicy::default().max_attempts as usize;
+        let provider = Arc::new(FlakyProvider {
+            failures,
+            calls: AtomicUsize::new(0),
+        });
+        let (audio_tx, audio_rx) = mpsc::unbounded_channel();
+        audio_tx.send(vec![0.1; 16000]).unwrap();
+        drop(audio_tx);
+
+        let transcript = transcribe_while_recording(
+            provider.clone(),
+            audio_rx,
+            16000,
+            1,
+            &TranscriptionOptions::default(),
+            EncodingOptions::default(),
+            None,
+            &CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(transcript.text, "hello");
+        assert_eq!(provider.calls.load(Ordering::SeqCst), failures + 1);
+    }
+}
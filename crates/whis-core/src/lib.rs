@@ -1,11 +1,45 @@
 pub mod audio;
 pub mod clipboard;
 pub mod config;
+pub mod decode;
+pub mod dsp;
+pub mod encoder;
+pub mod error;
+pub mod incremental;
+pub mod model;
+pub mod preprocess;
+pub mod provider;
+pub mod realtime;
+pub mod retry;
 pub mod settings;
+pub mod source;
 pub mod transcribe;
+pub mod transcript;
+pub mod vad;
 
-pub use audio::{AudioChunk, AudioRecorder, RecordingData, RecordingOutput};
+/// Token for cancelling an in-flight transcription
+pub use tokio_util::sync::CancellationToken;
+
+pub use audio::{
+    AudioChunk, AudioRecorder, AutoStop, InputDevice, InputLevel, RecordingData, RecordingOutput,
+    list_input_devices,
+};
 pub use clipboard::copy_to_clipboard;
 pub use config::ApiConfig;
-pub use settings::Settings;
-pub use transcribe::{ChunkTranscription, parallel_transcribe, transcribe_audio};
+pub use decode::{PcmFormat, decode_bytes, decode_file, decode_raw};
+pub use encoder::{AudioEncoder, EncodingOptions, EncodingProfile};
+pub use error::{Result, WhisError};
+pub use incremental::transcribe_while_recording;
+pub use preprocess::Preprocessing;
+pub use provider::{
+    OpenAIProvider, TranscriptionOptions, TranscriptionProvider, TranscriptionRequest,
+};
+pub use realtime::{RealtimeConfig, RealtimeEvent, stream_transcription};
+pub use settings::{MAX_PRE_ROLL_MS, Settings};
+pub use source::{AudioSource, FileSource, InputStream, MicrophoneSource, SampleSink, ToneSource};
+pub use transcribe::{
+    ChunkTranscription, Progress, ProgressCallback, cancellable, parallel_transcribe, transcribe,
+    transcribe_audio, transcribe_blocking, transcribe_recording,
+};
+pub use transcript::{OutputFormat, Segment, Transcript, Word};
+pub use vad::{TimeMap, VadAggressiveness};
@@ -0,0 +1,168 @@
+//! Local Whisper model management
+//!
+//! GGML models are downloaded from the whisper.cpp Hugging Face repository into
+//! `~/.local/share/whis/models` (or `Settings::models_dir`).
+
+use anyhow::Context;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+use crate::error::Result;
+use crate::settings::Settings;
+
+/// Where ggml models are downloaded from
+const MODEL_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+/// Download timeout in seconds (large models are several GB)
+const DOWNLOAD_TIMEOUT_SECS: u64 = 3600;
+
+/// A downloadable GGML Whisper model
+pub struct WhisperModel {
+    pub name: &'static str,
+    pub size_mb: u32,
+    pub description: &'static str,
+}
+
+/// Models available for download
+pub const MODELS: &[WhisperModel] = &[
+    WhisperModel {
+        name: "tiny",
+        size_mb: 75,
+        description: "Fastest, lowest accuracy",
+    },
+    WhisperModel {
+        name: "tiny.en",
+        size_mb: 75,
+        description: "Fastest, English only",
+    },
+    WhisperModel {
+        name: "base",
+        size_mb: 142,
+        description: "Fast, good for short dictation",
+    },
+    WhisperModel {
+        name: "base.en",
+        size_mb: 142,
+        description: "Fast, English only",
+    },
+    WhisperModel {
+        name: "small",
+        size_mb: 466,
+        description: "Balanced speed and accuracy",
+    },
+    WhisperModel {
+        name: "small.en",
+        size_mb: 466,
+        description: "Balanced, English only",
+    },
+    WhisperModel {
+        name: "medium",
+        size_mb: 1500,
+        description: "High accuracy, slow on CPU",
+    },
+    WhisperModel {
+        name: "medium.en",
+        size_mb: 1500,
+        description: "High accuracy, English only",
+    },
+    WhisperModel {
+        name: "large-v3-turbo",
+        size_mb: 1600,
+        description: "Near large-v3 accuracy, much faster",
+    },
+    WhisperModel {
+        name: "large-v3",
+        size_mb: 2900,
+        description: "Best accuracy, very slow on CPU",
+    },
+];
+
+/// Whether this build can run local models (`local-whisper` feature)
+pub const fn local_transcription_available() -> bool {
+    cfg!(feature = "local-whisper")
+}
+
+/// Look up a model in the catalogue
+pub fn find_model(name: &str) -> Option<&'static WhisperModel> {
+    MODELS.iter().find(|m| m.name == name)
+}
+
+/// Directory where models are stored
+pub fn models_dir(settings: &Settings) -> PathBuf {
+    settings.models_dir.clone().unwrap_or_else(|| {
+        dirs::data_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("whis")
+            .join("models")
+    })
+}
+
+/// Resolve a model name (or a path to a .bin file) to its file path
+pub fn model_path(settings: &Settings, model: &str) -> PathBuf {
+    if model.ends_with(".bin") || model.contains(std::path::MAIN_SEPARATOR) {
+        return PathBuf::from(model);
+    }
+    models_dir(settings).join(format!("ggml-{model}.bin"))
+}
+
+/// Check whether a model file is present
+pub fn is_downloaded(settings: &Settings, model: &str) -> bool {
+    model_path(settings, model).is_file()
+}
+
+/// Download a model from the catalogue (blocking).
+///
+/// `progress` is called with the bytes downloaded so far and the total size if known.
+pub fn download_model(
+    settings: &Settings,
+    name: &str,
+    mut progress: impl FnMut(u64, Option<u64>),
+) -> Result<PathBuf> {
+    let model = find_model(name).with_context(|| format!("Unknown model: {name}"))?;
+    let path = model_path(settings, model.name);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).context("Failed to create models directory")?;
+    }
+
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(DOWNLOAD_TIMEOUT_SECS))
+        .build()
+        .context("Failed to create HTTP client")?;
+
+    let url = format!("{MODEL_BASE_URL}/ggml-{}.bin", model.name);
+    let mut response = client
+        .get(&url)
+        .send()
+        .with_context(|| format!("Failed to download {url}"))?;
+
+    if !response.status().is_success() {
+        return Err(anyhow::anyhow!("Model download failed ({})", response.status()).into());
+    }
+
+    let total = response.content_length();
+
+    // Download to a temporary file so an interrupted download is never mistaken for a model
+    let part_path = path.with_extension("bin.part");
+    let mut file = std::fs::File::create(&part_path).context("Failed to create model file")?;
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut downloaded = 0u64;
+
+    loop {
+        let n = response
+            .read(&mut buffer)
+            .context("Failed to read model data")?;
+        if n == 0 {
+            break;
+        }
+        file.write_all(&buffer[..n])
+            .context("Failed to write model file")?;
+        downloaded += n as u64;
+        progress(downloaded, total);
+    }
+
+    file.flush().context("Failed to write model file")?;
+    drop(file);
+    std::fs::rename(&part_path, &path).context("Failed to move downloaded model into place")?;
+
+    Ok(path)
+}
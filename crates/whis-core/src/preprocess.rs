@@ -0,0 +1,382 @@
+//! Clean-up of noisy recordings before they are encoded
+//!
+//! Laptop microphones in open offices pick up rumble, fans and chatter, which costs
+//! transcription accuracy. Three optional stages run in order: a high-pass filter
+//! against rumble and hum, spectral noise suppression against steady background
+//! noise, and loudness normalization so quiet speakers reach a level Whisper handles
+//! well. All of them keep the length of the recording, so timestamps still line up.
+
+use realfft::RealFftPlanner;
+use realfft::num_complex::Complex;
+use std::f32::consts::PI;
+
+use crate::error::{Result, WhisError};
+use crate::settings::Settings;
+
+/// Cutoff of the high-pass filter; speech has next to nothing below it
+const HIGH_PASS_HZ: f32 = 80.0;
+/// Length of a noise suppression frame (rounded up to a power of two)
+const DENOISE_FRAME_MS: usize = 20;
+/// Share of the quietest frames, in percent, that make up the noise estimate
+const NOISE_PERCENTILE: usize = 10;
+/// Lowest gain of a frequency bin (-20 dB), so noise is turned down rather than gated
+const MIN_GAIN: f32 = 0.1;
+/// Weight of the previous frame in the speech-to-noise estimate; smoothing it over
+/// time keeps random noise peaks from leaking through as "musical" noise
+const SNR_SMOOTHING: f32 = 0.98;
+/// Loudness normalized recordings end up at, in dBFS
+const TARGET_LOUDNESS_DB: f32 = -20.0;
+/// Normalization never pushes peaks above this level
+const MAX_PEAK_DB: f32 = -1.0;
+/// Normalization never amplifies more than this, so near-silence stays quiet
+const MAX_GAIN_DB: f32 = 30.0;
+/// Length of a loudness measurement block
+const LOUDNESS_BLOCK_MS: usize = 50;
+/// Blocks below this level are silence and don't count towards loudness
+const ABSOLUTE_GATE_DB: f32 = -60.0;
+/// Blocks this far below the average are pauses and don't count either
+const RELATIVE_GATE_DB: f32 = 10.0;
+
+/// Which clean-up stages run before encoding
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Preprocessing {
+    /// Suppress steady background noise
+    pub denoise: bool,
+    /// Cut rumble and hum below 80 Hz
+    pub high_pass: bool,
+    /// Bring speech to a consistent loudness
+    pub normalize: bool,
+}
+
+impl Preprocessing {
+    /// Clean-up stages turned on in settings
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            denoise: settings.denoise,
+            high_pass: settings.high_pass,
+            normalize: settings.normalize,
+        }
+    }
+
+    /// Whether any stage is turned on
+    pub fn is_enabled(&self) -> bool {
+        self.denoise || self.high_pass || self.normalize
+    }
+}
+
+/// Run the enabled stages over interleaved samples
+pub fn preprocess(
+    mut samples: Vec<f32>,
+    sample_rate: u32,
+    channels: u16,
+    stages: &Preprocessing,
+) -> Result<Vec<f32>> {
+    let channels = channels.max(1) as usize;
+    if stages.high_pass {
+        for channel in 0..channels {
+            high_pass(&mut samples, channel, channels, sample_rate);
+        }
+    }
+    if stages.denoise {
+        for channel in 0..channels {
+            let plane: Vec<f32> = samples
+                .iter()
+                .skip(channel)
+                .step_by(channels)
+                .copied()
+                .collect();
+            let cleaned = denoise(&plane, sample_rate)?;
+            for (sample, clean) in samples
+                .iter_mut()
+                .skip(channel)
+                .step_by(channels)
+                .zip(cleaned)
+            {
+                *sample = clean;
+            }
+        }
+    }
+    if stages.normalize {
+        normalize(&mut samples, sample_rate, channels);
+    }
+    Ok(samples)
+}
+
+/// Second-order Butterworth high-pass at [`HIGH_PASS_HZ`] over one channel of interleaved samples
+fn high_pass(samples: &mut [f32], channel: usize, channels: usize, sample_rate: u32) {
+    // Biquad coefficients from the RBJ audio EQ cookbook
+    let w0 = 2.0 * std::f64::consts::PI * HIGH_PASS_HZ as f64 / sample_rate as f64;
+    let (sin, cos) = w0.sin_cos();
+    let alpha = sin / std::f64::consts::SQRT_2;
+    let a0 = 1.0 + alpha;
+    let b0 = (1.0 + cos) / 2.0 / a0;
+    let b1 = -(1.0 + cos) / a0;
+    let b2 = b0;
+    let a1 = -2.0 * cos / a0;
+    let a2 = (1.0 - alpha) / a0;
+
+    // Transposed direct form II, with the state in f64 to keep the low cutoff stable
+    let (mut z1, mut z2) = (0.0, 0.0);
+    for sample in samples.iter_mut().skip(channel).step_by(channels) {
+        let x = *sample as f64;
+        let y = b0 * x + z1;
+        z1 = b1 * x - a1 * y + z2;
+        z2 = b2 * x - a2 * y;
+        *sample = y as f32;
+    }
+}
+
+/// Spectral noise suppression of mono samples: the noise spectrum is estimated from the
+/// quietest frames and every frequency bin is turned down by how much noise it holds
+/// (Wiener filter with the decision-directed SNR estimate of Ephraim and Malah)
+fn denoise(samples: &[f32], sample_rate: u32) -> Result<Vec<f32>> {
+    let frame_len = (sample_rate as usize * DENOISE_FRAME_MS / 1000).next_power_of_two();
+    let hop = frame_len / 2;
+    if samples.len() < 2 * frame_len {
+        return Ok(samples.to_vec());
+    }
+
+    // Pad by a hop on both sides so every sample is covered by two frames
+    let mut padded = vec![0.0; hop];
+    padded.extend_from_slice(samples);
+    padded.resize((padded.len().div_ceil(hop) + 1) * hop, 0.0);
+    let frame_count = (padded.len() - frame_len) / hop + 1;
+
+    // Square-root Hann windows at 50% overlap add up to one, so kept audio is unchanged
+    let window: Vec<f32> = (0..frame_len)
+        .map(|i| (PI * i as f32 / frame_len as f32).sin())
+        .collect();
+    let mut planner = RealFftPlanner::<f32>::new();
+    let forward = planner.plan_fft_forward(frame_len);
+    let inverse = planner.plan_fft_inverse(frame_len);
+    let mut buffer = forward.make_input_vec();
+    let mut spectrum = forward.make_output_vec();
+    let mut analyze = |start: usize, spectrum: &mut [Complex<f32>]| {
+        for ((input, sample), weight) in buffer.iter_mut().zip(&padded[start..]).zip(&window) {
+            *input = sample * weight;
+        }
+        forward
+            .process(&mut buffer, spectrum)
+            .map_err(denoise_error)
+    };
+
+    // Noise spectrum: average power of the quietest frames that hold no padding
+    let mut quiet: Vec<(f32, usize)> = (1..frame_count - 1)
+        .map(|frame| {
+            let start = frame * hop;
+            let energy = padded[start..start + frame_len].iter().map(|s| s * s).sum();
+            (energy, start)
+        })
+        .collect();
+    if quiet.is_empty() {
+        return Ok(samples.to_vec());
+    }
+    quiet.sort_by(|a, b| a.0.total_cmp(&b.0));
+    quiet.truncate((quiet.len() * NOISE_PERCENTILE / 100).max(1));
+    let mut noise = vec![0.0f32; spectrum.len()];
+    for &(_, start) in &quiet {
+        analyze(start, &mut spectrum)?;
+        for (noise, bin) in noise.iter_mut().zip(&spectrum) {
+            *noise += bin.norm_sqr() / quiet.len() as f32;
+        }
+    }
+
+    let mut output = vec![0.0; padded.len()];
+    // Estimated clean power of each bin in the previous frame
+    let mut previous = vec![0.0f32; spectrum.len()];
+    let mut frame = inverse.make_output_vec();
+    let scale = 1.0 / frame_len as f32;
+    for start in (0..frame_count).map(|frame| frame * hop) {
+        analyze(start, &mut spectrum)?;
+        for ((bin, previous), noise) in spectrum.iter_mut().zip(&mut previous).zip(&noise) {
+            let noise = noise.max(f32::MIN_POSITIVE);
+            let power = bin.norm_sqr();
+            let snr = SNR_SMOOTHING * *previous / noise
+                + (1.0 - SNR_SMOOTHING) * (power / noise - 1.0).max(0.0);
+            let gain = (snr / (1.0 + snr)).max(MIN_GAIN);
+            *previous = gain * gain * power;
+            *bin *= gain;
+        }
+        inverse
+            .process(&mut spectrum, &mut frame)
+            .map_err(denoise_error)?;
+        for ((out, sample), weight) in output[start..].iter_mut().zip(&frame).zip(&window) {
+            *out += sample * weight * scale;
+        }
+    }
+
+    Ok(output[hop..hop + samples.len()].to_vec())
+}
+
+/// Scale interleaved samples to [`TARGET_LOUDNESS_DB`], measured over the blocks that
+/// aren't silence or pauses (gated like EBU R 128) and limited by the peak level
+fn normalize(samples: &mut [f32], sample_rate: u32, channels: usize) {
+    let block_len = (sample_rate as usize * LOUDNESS_BLOCK_MS / 1000).max(1) * channels;
+    let loud: Vec<f32> = samples
+        .chunks(block_len)
+        .map(mean_square)
+        .filter(|&power| power_db(power) > ABSOLUTE_GATE_DB)
+        .collect();
+    if loud.is_empty() {
+        return;
+    }
+    let relative_gate = power_db(loud.iter().sum::<f32>() / loud.len() as f32) - RELATIVE_GATE_DB;
+    let speech: Vec<f32> = loud
+        .into_iter()
+        .filter(|&power| power_db(power) > relative_gate)
+        .collect();
+    let loudness_db = power_db(speech.iter().sum::<f32>() / speech.len().max(1) as f32);
+
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    let gain_db = (TARGET_LOUDNESS_DB - loudness_db)
+        .min(MAX_GAIN_DB)
+        .min(MAX_PEAK_DB - 20.0 * peak.max(f32::MIN_POSITIVE).log10());
+    let gain = 10f32.powf(gain_db / 20.0);
+    for sample in samples {
+        *sample *= gain;
+    }
+}
+
+fn mean_square(samples: &[f32]) -> f32 {
+    samples.iter().map(|s| s * s).sum::<f32>() / samples.len().max(1) as f32
+}
+
+fn power_db(mean_square: f32) -> f32 {
+    10.0 * mean_square.max(1e-10).log10()
+}
+
+fn denoise_error(err: impl std::fmt::Display) -> WhisError {
+    WhisError::Encoding(format!("Failed to suppress noise: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 16000;
+
+    fn sine(freq: f32, amplitude: f32, secs: f32) -> Vec<f32> {
+        (0..(RATE as f32 * secs) as usize)
+            .map(|i| amplitude * (2.0 * PI * freq * i as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    fn white_noise(amplitude: f32, len: usize, seed: u64) -> Vec<f32> {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        (0..len)
+            .map(|_| amplitude * (rng.f32() * 2.0 - 1.0))
+            .collect()
+    }
+
+    fn rms_db(samples: &[f32]) -> f32 {
+        power_db(mean_square(samples))
+    }
+
+    fn only(stage: fn(&mut Preprocessing)) -> Preprocessing {
+        let mut stages = Preprocessing::default();
+        stage(&mut stages);
+        stages
+    }
+
+    #[test]
+    fn test_disabled_stages_leave_audio_untouched() {
+        let samples = white_noise(0.3, 4000, 1);
+        let output = preprocess(samples.clone(), RATE, 1, &Preprocessing::default()).unwrap();
+        assert_eq!(output, samples);
+    }
+
+    #[test]
+    fn test_high_pass_removes_hum_and_keeps_speech_band() {
+        let stages = only(|s| s.high_pass = true);
+        // Skip the filter's settling time at the start
+        let settled = RATE as usize / 4..;
+
+        let hum = preprocess(sine(30.0, 0.5, 1.0), RATE, 1, &stages).unwrap();
+        assert!(rms_db(&hum[settled.clone()]) < rms_db(&sine(30.0, 0.5, 1.0)) - 12.0);
+
+        let tone = sine(1000.0, 0.5, 1.0);
+        let filtered = preprocess(tone.clone(), RATE, 1, &stages).unwrap();
+        assert!((rms_db(&filtered[settled]) - rms_db(&tone)).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_high_pass_filters_channels_separately() {
+        // Left: hum, right: 1 kHz tone
+        let (hum, tone) = (sine(30.0, 0.5, 1.0), sine(1000.0, 0.5, 1.0));
+        let stereo: Vec<f32> = hum.iter().zip(&tone).flat_map(|(&l, &r)| [l, r]).collect();
+        let output = preprocess(stereo, RATE, 2, &only(|s| s.high_pass = true)).unwrap();
+
+        let settled = RATE as usize / 4 * 2..;
+        let left: Vec<f32> = output[settled.clone()].iter().step_by(2).copied().collect();
+        let right: Vec<f32> = output[settled].iter().skip(1).step_by(2).copied().collect();
+        assert!(rms_db(&left) < rms_db(&hum) - 12.0);
+        assert!((rms_db(&right) - rms_db(&tone)).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_denoise_suppresses_noise_between_speech() {
+        // One second of tone, one of pause, with steady noise throughout
+        let mut clean = sine(440.0, 0.3, 1.0);
+        clean.resize(clean.len() * 2, 0.0);
+        clean = clean.repeat(3);
+        let noise = white_noise(0.03, clean.len(), 2);
+        let noisy: Vec<f32> = clean.iter().zip(&noise).map(|(s, n)| s + n).collect();
+
+        let output = preprocess(noisy.clone(), RATE, 1, &only(|s| s.denoise = true)).unwrap();
+        assert_eq!(output.len(), noisy.len());
+
+        // Pause in the middle of the recording: noise turned down by well over 10 dB
+        let pause = (RATE as usize * 31 / 10)..(RATE as usize * 39 / 10);
+        assert!(rms_db(&output[pause.clone()]) < rms_db(&noisy[pause]) - 12.0);
+
+        // Tone kept at about its level, and closer to the clean signal than before
+        let tone = (RATE as usize * 21 / 10)..(RATE as usize * 29 / 10);
+        assert!((rms_db(&output[tone.clone()]) - rms_db(&clean[tone.clone()])).abs() < 1.0);
+        let error = |signal: &[f32]| {
+            let diff: Vec<f32> = signal
+                .iter()
+                .zip(&clean[tone.clone()])
+                .map(|(a, b)| a - b)
+                .collect();
+            rms_db(&diff)
+        };
+        assert!(error(&output[tone.clone()]) < error(&noisy[tone.clone()]));
+    }
+
+    #[test]
+    fn test_denoise_keeps_short_recordings() {
+        let samples = white_noise(0.1, 100, 3);
+        let output = preprocess(samples.clone(), RATE, 1, &only(|s| s.denoise = true)).unwrap();
+        assert_eq!(output, samples);
+    }
+
+    #[test]
+    fn test_normalize_brings_quiet_speech_to_target() {
+        // Quiet tone with long pauses, which must not drag the measured loudness down
+        let mut quiet = sine(300.0, 0.01, 1.0);
+        quiet.extend(vec![0.0; RATE as usize * 3]);
+        let output = preprocess(quiet, RATE, 1, &only(|s| s.normalize = true)).unwrap();
+        assert!((rms_db(&output[..RATE as usize]) - TARGET_LOUDNESS_DB).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_normalize_turns_down_loud_speech_without_clipping() {
+        let loud = sine(300.0, 0.9, 1.0);
+        let output = preprocess(loud, RATE, 1, &only(|s| s.normalize = true)).unwrap();
+        assert!((rms_db(&output) - TARGET_LOUDNESS_DB).abs() < 0.5);
+
+        // A sharp click limits the gain so its peak stays below full scale
+        let mut clicky = sine(300.0, 0.005, 1.0);
+        clicky[100] = 0.5;
+        let output = preprocess(clicky, RATE, 1, &only(|s| s.normalize = true)).unwrap();
+        let peak = output.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!(20.0 * peak.log10() <= MAX_PEAK_DB + 0.01);
+    }
+
+    #[test]
+    fn test_normalize_leaves_silence_alone() {
+        let silence = white_noise(1e-5, 8000, 4);
+        let output = preprocess(silence.clone(), RATE, 1, &only(|s| s.normalize = true)).unwrap();
+        assert_eq!(output, silence);
+    }
+}
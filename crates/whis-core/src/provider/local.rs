@@ -0,0 +1,105 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use std::path::Path;
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+use super::{TranscriptionOptions, TranscriptionProvider, TranscriptionRequest};
+use crate::audio::WHISPER_SAMPLE_RATE;
+use crate::error::Result;
+use crate::transcript::{Segment, Transcript};
+
+/// Offline transcription with a GGML Whisper model via whisper.cpp.
+///
+/// Runs on the CPU and reads raw 16 kHz mono PCM, so recordings never touch
+/// the network or the MP3 encoder.
+pub struct LocalWhisperProvider {
+    context: WhisperContext,
+}
+
+impl LocalWhisperProvider {
+    /// Load a GGML model from disk (takes a moment for larger models)
+    pub fn load(model_path: &Path) -> Result<Self> {
+        let context =
+            WhisperContext::new_with_params(model_path, WhisperContextParameters::default())
+                .with_context(|| {
+                    format!("Failed to load Whisper model from {}", model_path.display())
+                })?;
+        Ok(Self { context })
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for LocalWhisperProvider {
+    fn name(&self) -> &'static str {
+        "Local Whisper"
+    }
+
+    async fn transcribe(
+        &self,
+        _client: &reqwest::Client,
+        _request: TranscriptionRequest,
+    ) -> Result<Transcript> {
+        Err(anyhow::anyhow!(
+            "Local Whisper reads raw PCM; finalize the recording with finalize_for()"
+        )
+        .into())
+    }
+
+    fn accepts_pcm(&self) -> bool {
+        true
+    }
+
+    fn transcribe_pcm(
+        &self,
+        samples: Vec<f32>,
+        options: &TranscriptionOptions,
+    ) -> Result<Transcript> {
+        let mut state = self
+            .context
+            .create_state()
+            .context("Failed to create Whisper state")?;
+
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        // The model is chosen by the loaded file, so `options.model` doesn't apply here
+        params.set_language(Some(options.language.as_deref().unwrap_or("auto")));
+        if let Some(prompt) = &options.prompt {
+            params.set_initial_prompt(prompt);
+        }
+        if let Some(temperature) = options.temperature {
+            params.set_temperature(temperature);
+        }
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_special(false);
+        params.set_print_timestamps(false);
+        let threads = std::thread::available_parallelism().map_or(4, |n| n.get());
+        params.set_n_threads(threads as i32);
+
+        state
+            .full(params, &samples)
+            .context("Local transcription failed")?;
+
+        // Segment timestamps are in units of 10 ms
+        let mut text = String::new();
+        let mut segments = Vec::new();
+        for segment in state.as_iter() {
+            let segment_text = segment
+                .to_str_lossy()
+                .context("Failed to read segment text")?;
+            text.push_str(segment_text.as_ref());
+            segments.push(Segment {
+                start: segment.start_timestamp() as f64 / 100.0,
+                end: segment.end_timestamp() as f64 / 100.0,
+                text: segment_text.trim().to_string(),
+            });
+        }
+
+        Ok(Transcript {
+            text: text.trim().to_string(),
+            language: options.language.clone(),
+            duration: Some(samples.len() as f64 / WHISPER_SAMPLE_RATE as f64),
+            segments,
+            words: Vec::new(),
+        })
+    }
+}
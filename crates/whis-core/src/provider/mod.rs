@@ -0,0 +1,111 @@
+//! Transcription providers
+//!
+//! A provider turns an encoded audio file (or raw PCM, for local models) into a [`Transcript`]. `transcribe_audio` and
+//! `parallel_transcribe` only talk to the [`TranscriptionProvider`] trait, so new
+//! backends can be added without touching the chunking and merging logic.
+
+#[cfg(feature = "local-whisper")]
+mod local;
+mod openai;
+
+#[cfg(feature = "local-whisper")]
+pub use local::LocalWhisperProvider;
+pub use openai::OpenAIProvider;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+use crate::config::ApiConfig;
+use crate::error::{Result, WhisError};
+use crate::settings::Settings;
+use crate::transcript::Transcript;
+
+/// Per-request transcription options. `None` leaves the choice to the provider.
+#[derive(Debug, Clone, Default)]
+pub struct TranscriptionOptions {
+    /// Model name (e.g. "gpt-4o-transcribe"), overrides the provider default
+    pub model: Option<String>,
+    /// Spoken language as ISO-639-1 code (e.g. "en", "de")
+    pub language: Option<String>,
+    /// Prompt to guide style or spelling of names and jargon
+    pub prompt: Option<String>,
+    /// Sampling temperature between 0 and 1
+    pub temperature: Option<f32>,
+}
+
+impl TranscriptionOptions {
+    /// Options saved in settings
+    pub fn from_settings(settings: &Settings) -> Self {
+        Self {
+            model: settings.model.clone(),
+            language: settings.language.clone(),
+            prompt: settings.prompt.clone(),
+            temperature: settings.temperature,
+        }
+    }
+}
+
+/// Encoded audio handed to a provider
+#[derive(Clone)]
+pub struct TranscriptionRequest {
+    /// Encoded audio data
+    pub audio_data: Vec<u8>,
+    /// File name for the upload (providers use the extension to detect the format)
+    pub filename: String,
+    /// MIME type of `audio_data` (e.g. "audio/flac")
+    pub mime_type: &'static str,
+    /// Model, language, prompt and temperature
+    pub options: TranscriptionOptions,
+}
+
+/// A backend that can transcribe encoded audio
+#[async_trait]
+pub trait TranscriptionProvider: Send + Sync {
+    /// Human-readable provider name, used in log and error messages
+    fn name(&self) -> &'static str;
+
+    /// Transcribe a request using a shared async HTTP client
+    async fn transcribe(
+        &self,
+        client: &reqwest::Client,
+        request: TranscriptionRequest,
+    ) -> Result<Transcript>;
+
+    /// Whether this provider reads raw 16 kHz mono PCM instead of encoded audio.
+    /// Recordings for such providers skip MP3 encoding entirely.
+    fn accepts_pcm(&self) -> bool {
+        false
+    }
+
+    /// Transcribe raw 16 kHz mono samples (blocking)
+    fn transcribe_pcm(
+        &self,
+        _samples: Vec<f32>,
+        _options: &TranscriptionOptions,
+    ) -> Result<Transcript> {
+        Err(anyhow::anyhow!("{} does not accept raw PCM audio", self.name()).into())
+    }
+}
+
+/// Build the provider selected in settings: the local model if one is set,
+/// otherwise the OpenAI(-compatible) API.
+pub fn from_settings(settings: &Settings) -> Result<Arc<dyn TranscriptionProvider>> {
+    if let Some(model) = &settings.local_model {
+        return local_provider(settings, model);
+    }
+    Ok(ApiConfig::from_settings(settings)?.provider())
+}
+
+#[cfg(feature = "local-whisper")]
+fn local_provider(settings: &Settings, model: &str) -> Result<Arc<dyn TranscriptionProvider>> {
+    let path = crate::model::model_path(settings, model);
+    if !path.is_file() {
+        return Err(WhisError::ModelNotDownloaded(model.to_string()));
+    }
+    Ok(Arc::new(LocalWhisperProvider::load(&path)?))
+}
+
+#[cfg(not(feature = "local-whisper"))]
+fn local_provider(_settings: &Settings, model: &str) -> Result<Arc<dyn TranscriptionProvider>> {
+    Err(WhisError::LocalWhisperUnavailable(model.to_string()))
+}
@@ -0,0 +1,330 @@
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use std::time::Duration;
+
+use super::{TranscriptionOptions, TranscriptionProvider, TranscriptionRequest};
+use crate::error::{Result, WhisError};
+use crate::transcript::Transcript;
+
+/// OpenAI API base URL
+const OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+/// Default model used for transcription
+const DEFAULT_MODEL: &str = "whisper-1";
+
+/// OpenAI Whisper API provider.
+///
+/// Also talks to self-hosted servers that implement the same
+/// `/v1/audio/transcriptions` protocol (faster-whisper, whisper.cpp server, ...).
+pub struct OpenAIProvider {
+    api_key: Option<String>,
+    url: String,
+    model: String,
+    is_custom: bool,
+}
+
+impl OpenAIProvider {
+    /// Provider for the hosted OpenAI API
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: Some(api_key.into()),
+            url: transcriptions_url(OPENAI_BASE_URL),
+            model: DEFAULT_MODEL.to_string(),
+            is_custom: false,
+        }
+    }
+
+    /// Provider for an OpenAI-compatible server at `base_url` (e.g. "http://localhost:8000/v1")
+    pub fn compatible(base_url: &str, api_key: Option<String>) -> Self {
+        Self {
+            api_key: api_key.filter(|key| !key.is_empty()),
+            url: transcriptions_url(base_url),
+            model: DEFAULT_MODEL.to_string(),
+            is_custom: true,
+        }
+    }
+
+    /// Override the model sent with each request
+    pub fn with_model(mut self, model: impl Into<String>) -> Self {
+        self.model = model.into();
+        self
+    }
+}
+
+/// Build the transcriptions endpoint from a base URL
+fn transcriptions_url(base_url: &str) -> String {
+    format!("{}/audio/transcriptions", base_url.trim_end_matches('/'))
+}
+
+/// Whether the model can return `verbose_json` with timestamps.
+/// The GPT-4o transcription models only support plain `json`.
+fn supports_timestamps(model: &str) -> bool {
+    !model.starts_with("gpt-4o")
+}
+
+/// Text fields of the multipart form (model, language, prompt, temperature, response format)
+fn form_fields(default_model: &str, options: &TranscriptionOptions) -> Vec<(&'static str, String)> {
+    let model = options
+        .model
+        .clone()
+        .unwrap_or_else(|| default_model.to_string());
+    let mut fields = Vec::new();
+    if supports_timestamps(&model) {
+        fields.push(("response_format", "verbose_json".to_string()));
+        fields.push(("timestamp_granularities[]", "segment".to_string()));
+        fields.push(("timestamp_granularities[]", "word".to_string()));
+    } else {
+        fields.push(("response_format", "json".to_string()));
+    }
+    fields.push(("model", model));
+    if let Some(language) = &options.language {
+        fields.push(("language", language.clone()));
+    }
+    if let Some(prompt) = &options.prompt {
+        fields.push(("prompt", prompt.clone()));
+    }
+    if let Some(temperature) = options.temperature {
+        fields.push(("temperature", temperature.to_string()));
+    }
+    fields
+}
+
+/// Classify a request that never got a response. Only timeouts and failed
+/// connections are worth retrying; other request errors (building the request,
+/// sending or reading the body) come back as they are.
+fn send_error(provider: &'static str, err: reqwest::Error) -> WhisError {
+    if err.is_timeout() {
+        WhisError::Timeout { provider }
+    } else if err.is_connect() {
+        WhisError::Network {
+            provider,
+            message: err.to_string(),
+        }
+    } else {
+        anyhow::Error::new(err)
+            .context(format!("Failed to send request to {provider}"))
+            .into()
+    }
+}
+
+/// Pull the message out of an OpenAI-style `{"error": {"message": ...}}` body
+fn error_message(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|json| json["error"]["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| body.trim().to_string())
+}
+
+/// Machine-readable code of an OpenAI-style error body (e.g. "insufficient_quota")
+fn error_code(body: &str) -> Option<String> {
+    let json = serde_json::from_str::<serde_json::Value>(body).ok()?;
+    let error = &json["error"];
+    error["code"]
+        .as_str()
+        .or_else(|| error["type"].as_str())
+        .map(str::to_string)
+}
+
+/// Classify a non-success response
+fn status_error(
+    provider: &'static str,
+    status: StatusCode,
+    retry_after: Option<Duration>,
+    body: &str,
+) -> WhisError {
+    let message = error_message(body);
+    match status {
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+            WhisError::Authentication { provider, message }
+        }
+        // OpenAI answers 429 for an empty balance too, which no amount of waiting fixes
+        StatusCode::TOO_MANY_REQUESTS
+            if error_code(body).as_deref() == Some("insufficient_quota") =>
+        {
+            WhisError::QuotaExceeded { provider, message }
+        }
+        StatusCode::TOO_MANY_REQUESTS => WhisError::RateLimited {
+            provider,
+            message,
+            retry_after,
+        },
+        _ => WhisError::Api {
+            provider,
+            status: status.as_u16(),
+            message,
+            retry_after,
+        },
+    }
+}
+
+/// Parse the JSON body of a successful transcription response
+/// (`json` or `verbose_json`, the latter with segments and words)
+fn parse_response(provider: &'static str, body: &str) -> Result<Transcript> {
+    serde_json::from_str(body).map_err(|e| WhisError::BadResponse {
+        provider,
+        message: e.to_string(),
+    })
+}
+
+#[async_trait]
+impl TranscriptionProvider for OpenAIProvider {
+    fn name(&self) -> &'static str {
+        if self.is_custom {
+            "OpenAI-compatible server"
+        } else {
+            "OpenAI"
+        }
+    }
+
+    async fn transcribe(
+        &self,
+        client: &reqwest::Client,
+        request: TranscriptionRequest,
+    ) -> Result<Transcript> {
+        let mut form = reqwest::multipart::Form::new().part(
+            "file",
+            reqwest::multipart::Part::bytes(request.audio_data)
+                .file_name(request.filename)
+                .mime_str(request.mime_type)
+                .map_err(anyhow::Error::from)?,
+        );
+        for (name, value) in form_fields(&self.model, &request.options) {
+            form = form.text(name, value);
+        }
+
+        let mut request_builder = client.post(&self.url).multipart(form);
+        if let Some(api_key) = &self.api_key {
+            request_builder = request_builder.bearer_auth(api_key);
+        }
+
+        let response = request_builder
+            .send()
+            .await
+            .map_err(|e| send_error(self.name(), e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let retry_after = crate::retry::parse_retry_after(response.headers());
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(status_error(self.name(), status, retry_after, &error_text));
+        }
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| send_error(self.name(), e))?;
+        parse_response(self.name(), &text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_body(message: &str, code: &str) -> String {
+        serde_json::json!({ "error": { "message": message, "type": "requests", "code": code } })
+            .to_string()
+    }
+
+    #[test]
+    fn test_rejected_key_is_an_authentication_error() {
+        for status in [StatusCode::UNAUTHORIZED, StatusCode::FORBIDDEN] {
+            let body = error_body("Incorrect API key provided", "invalid_api_key");
+            match status_error("OpenAI", status, None, &body) {
+                WhisError::Authentication { message, .. } => {
+                    assert_eq!(message, "Incorrect API key provided");
+                }
+                err => panic!("unexpected error: {err:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_too_many_requests_is_rate_limited() {
+        let body = error_body("Rate limit reached", "rate_limit_exceeded");
+        let retry_after = Some(Duration::from_secs(20));
+        match status_error("OpenAI", StatusCode::TOO_MANY_REQUESTS, retry_after, &body) {
+            WhisError::RateLimited {
+                message,
+                retry_after: Some(delay),
+                ..
+            } => {
+                assert_eq!(message, "Rate limit reached");
+                assert_eq!(delay, Duration::from_secs(20));
+            }
+            err => panic!("unexpected error: {err:?}"),
+        }
+    }
+
+    #[test]
+    fn test_insufficient_quota_is_quota_exceeded() {
+        let body = error_body("You exceeded your current quota", "insufficient_quota");
+        assert!(matches!(
+            status_error("OpenAI", StatusCode::TOO_MANY_REQUESTS, None, &body),
+            WhisError::QuotaExceeded { .. }
+        ));
+
+        // Some servers only set the type
+        let body = r#"{"error": {"message": "No credits", "type": "insufficient_quota"}}"#;
+        assert_eq!(error_code(body).as_deref(), Some("insufficient_quota"));
+        assert!(matches!(
+            status_error("OpenAI", StatusCode::TOO_MANY_REQUESTS, None, body),
+            WhisError::QuotaExceeded { .. }
+        ));
+    }
+
+    #[test]
+    fn test_other_statuses_are_api_errors() {
+        let retry_after = Some(Duration::from_secs(5));
+        match status_error(
+            "OpenAI",
+            StatusCode::SERVICE_UNAVAILABLE,
+            retry_after,
+            "upstream unavailable\n",
+        ) {
+            WhisError::Api {
+                status,
+                message,
+                retry_after,
+                ..
+            } => {
+                assert_eq!(status, 503);
+                // Bodies that aren't OpenAI errors are passed on as they are
+                assert_eq!(message, "upstream unavailable");
+                assert_eq!(retry_after, Some(Duration::from_secs(5)));
+            }
+            err => panic!("unexpected error: {err:?}"),
+        }
+
+        let body = error_body("Invalid file format", "invalid_value");
+        assert!(matches!(
+            status_error("OpenAI", StatusCode::BAD_REQUEST, None, &body),
+            WhisError::Api { status: 400, .. }
+        ));
+    }
+
+    #[test]
+    fn test_error_code() {
+        assert_eq!(
+            error_code(&error_body("Rate limit reached", "rate_limit_exceeded")).as_deref(),
+            Some("rate_limit_exceeded")
+        );
+        assert_eq!(error_code("Internal Server Error"), None);
+        assert_eq!(error_code(r#"{"error": {"message": "oops"}}"#), None);
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let transcript = parse_response("OpenAI", r#"{"text": "Hello there."}"#).unwrap();
+        assert_eq!(transcript.text, "Hello there.");
+
+        for body in ["<html>Bad Gateway</html>", r#"{"segments": []}"#, ""] {
+            assert!(matches!(
+                parse_response("OpenAI", body),
+                Err(WhisError::BadResponse { .. })
+            ));
+        }
+    }
+}
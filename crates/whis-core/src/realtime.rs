@@ -0,0 +1,368 @@
+//! Realtime streaming transcription over WebSocket
+//!
+//! Audio is pushed to an OpenAI-Realtime-style transcription session while the
+//! user is still speaking, so most of the text is ready by the time recording
+//! stops. The server segments speech with its own VAD; each segment is reported
+//! as partial deltas followed by a final transcript.
+
+use anyhow::Context;
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::{self, Message};
+
+use crate::config::ApiConfig;
+use crate::dsp::{self, StreamResampler};
+use crate::error::{Result, WhisError};
+use crate::provider::TranscriptionOptions;
+use crate::transcript::Transcript;
+
+/// OpenAI Realtime API base URL
+const OPENAI_REALTIME_URL: &str = "wss://api.openai.com/v1";
+/// Model used when none is configured
+const DEFAULT_REALTIME_MODEL: &str = "gpt-4o-transcribe";
+/// The realtime protocol expects 24 kHz mono PCM16
+const REALTIME_SAMPLE_RATE: u32 = 24000;
+/// How much audio to batch into one append message, in milliseconds
+const APPEND_INTERVAL_MS: u32 = 100;
+/// How long to wait for outstanding transcripts after recording stops
+const DRAIN_TIMEOUT_SECS: u64 = 30;
+/// Name used in error messages
+const PROVIDER: &str = "OpenAI Realtime";
+
+/// Connection settings for a realtime transcription session
+#[derive(Debug, Clone)]
+pub struct RealtimeConfig {
+    /// WebSocket URL including the `intent=transcription` query
+    pub url: String,
+    pub api_key: Option<String>,
+    pub model: String,
+    pub language: Option<String>,
+    pub prompt: Option<String>,
+}
+
+impl RealtimeConfig {
+    /// Derive the WebSocket endpoint from the API config (base URL, key) and transcription options
+    pub fn new(api: &ApiConfig, options: &TranscriptionOptions) -> Self {
+        let base = match &api.base_url {
+            Some(url) => url
+                .replacen("https://", "wss://", 1)
+                .replacen("http://", "ws://", 1),
+            None => OPENAI_REALTIME_URL.to_string(),
+        };
+        Self {
+            url: format!(
+                "{}/realtime?intent=transcription",
+                base.trim_end_matches('/')
+            ),
+            api_key: api.api_key.clone().filter(|key| !key.is_empty()),
+            model: options
+                .model
+                .clone()
+                .unwrap_or_else(|| DEFAULT_REALTIME_MODEL.to_string()),
+            language: options.language.clone(),
+            prompt: options.prompt.clone(),
+        }
+    }
+}
+
+/// Transcript updates sent while streaming
+#[derive(Debug, Clone, PartialEq)]
+pub enum RealtimeEvent {
+    /// Text so far for a speech segment that is still being transcribed
+    Partial { item_id: String, text: String },
+    /// Finished transcript of a speech segment
+    Final { item_id: String, text: String },
+}
+
+/// A speech segment committed by the server, in commit order
+struct Item {
+    id: String,
+    text: String,
+    done: bool,
+}
+
+/// Transcription progress of one session
+#[derive(Default)]
+struct Session {
+    items: Vec<Item>,
+    /// Speech ends detected by server VAD whose commit hasn't arrived yet.
+    /// Any other commit answers our own `input_audio_buffer.commit`.
+    vad_commits: usize,
+}
+
+/// Stream interleaved samples from `audio` (e.g. [`AudioRecorder::stream_samples`](crate::AudioRecorder::stream_samples))
+/// to a realtime transcription session.
+///
+/// Partial and final transcripts are sent to `events` as they arrive. When the
+/// audio channel closes (recording stopped), the remaining audio is committed and
+/// the full transcript is returned once every segment is final.
+pub async fn stream_transcription(
+    config: &RealtimeConfig,
+    mut audio: mpsc::UnboundedReceiver<Vec<f32>>,
+    sample_rate: u32,
+    channels: u16,
+    events: mpsc::UnboundedSender<RealtimeEvent>,
+) -> Result<Transcript> {
+    let mut request = config
+        .url
+        .as_str()
+        .into_client_request()
+        .context("Invalid realtime URL")?;
+    if let Some(key) = &config.api_key {
+        let value = HeaderValue::from_str(&format!("Bearer {key}")).context("Invalid API key")?;
+        request.headers_mut().insert("Authorization", value);
+    }
+    request
+        .headers_mut()
+        .insert("OpenAI-Beta", HeaderValue::from_static("realtime=v1"));
+
+    let (socket, _) = tokio_tungstenite::connect_async(request)
+        .await
+        .map_err(connect_error)?;
+    let (mut sink, mut stream) = socket.split();
+
+    send_json(&mut sink, &session_update(config)).await?;
+
+    let batch_len = (sample_rate * channels.max(1) as u32 * APPEND_INTERVAL_MS / 1000) as usize;
+    let mut pending: Vec<f32> = Vec::with_capacity(batch_len);
+    // One resampler for the whole session so batches join without clicks
+    let mut resampler = StreamResampler::new(sample_rate, REALTIME_SAMPLE_RATE)?;
+    let mut session = Session::default();
+
+    // Stream audio until the recording stops, handling server events as they come
+    loop {
+        tokio::select! {
+            samples = audio.recv() => match samples {
+                Some(samples) => {
+                    pending.extend_from_slice(&samples);
+                    if pending.len() >= batch_len {
+                        let mono = resampler.process(&dsp::downmix(&pending, channels))?;
+                        send_json(&mut sink, &append_message(&mono)).await?;
+                        pending.clear();
+                    }
+                }
+                None => break,
+            },
+            message = stream.next() => {
+                let Some(message) = message else {
+                    return Err(closed_error());
+                };
+                session.handle(message.map_err(socket_error)?, &events)?;
+            }
+        }
+    }
+
+    // Flush the tail and ask the server to transcribe whatever it hasn't segmented yet
+    let mut tail = resampler.process(&dsp::downmix(&pending, channels))?;
+    tail.extend(resampler.finish()?);
+    if !tail.is_empty() {
+        send_json(&mut sink, &append_message(&tail)).await?;
+    }
+    send_json(&mut sink, &json!({ "type": "input_audio_buffer.commit" })).await?;
+
+    let drain = async {
+        let mut commit_acknowledged = false;
+        while !(commit_acknowledged && session.items.iter().all(|item| item.done)) {
+            let Some(message) = stream.next().await else {
+                return Err(closed_error());
+            };
+            let message = message.map_err(socket_error)?;
+            if let Some(ServerEvent::Committed) = session.handle(message, &events)? {
+                commit_acknowledged = true;
+            }
+        }
+        Ok(())
+    };
+    tokio::time::timeout(Duration::from_secs(DRAIN_TIMEOUT_SECS), drain)
+        .await
+        .map_err(|_| WhisError::Timeout { provider: PROVIDER })??;
+
+    let _ = sink.send(Message::Close(None)).await;
+
+    let text = session
+        .items
+        .iter()
+        .map(|item| item.text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    Ok(Transcript::from_text(text))
+}
+
+/// Server events the streaming loop cares about
+enum ServerEvent {
+    /// The audio buffer was committed, or there was nothing left to commit
+    Committed,
+}
+
+impl Session {
+    /// Apply one server message, forwarding transcript updates
+    fn handle(
+        &mut self,
+        message: Message,
+        events: &mpsc::UnboundedSender<RealtimeEvent>,
+    ) -> Result<Option<ServerEvent>> {
+        let Message::Text(text) = message else {
+            return Ok(None);
+        };
+        let event: Value = serde_json::from_str(&text).map_err(|e| WhisError::BadResponse {
+            provider: PROVIDER,
+            message: e.to_string(),
+        })?;
+        let item_id = event["item_id"].as_str().unwrap_or_default();
+
+        match event["type"].as_str().unwrap_or_default() {
+            "input_audio_buffer.speech_stopped" => self.vad_commits += 1,
+            "input_audio_buffer.committed" => {
+                self.item(item_id);
+                if self.vad_commits > 0 {
+                    self.vad_commits -= 1;
+                } else {
+                    return Ok(Some(ServerEvent::Committed));
+                }
+            }
+            "conversation.item.input_audio_transcription.delta" => {
+                let item = self.item(item_id);
+                item.text
+                    .push_str(event["delta"].as_str().unwrap_or_default());
+                let _ = events.send(RealtimeEvent::Partial {
+                    item_id: item.id.clone(),
+                    text: item.text.clone(),
+                });
+            }
+            "conversation.item.input_audio_transcription.completed" => {
+                let item = self.item(item_id);
+                item.text = event["transcript"].as_str().unwrap_or_default().to_string();
+                item.done = true;
+                let _ = events.send(RealtimeEvent::Final {
+                    item_id: item.id.clone(),
+                    text: item.text.clone(),
+                });
+            }
+            "conversation.item.input_audio_transcription.failed" => {
+                // Don't wait forever for a segment the server gave up on
+                self.item(item_id).done = true;
+            }
+            "error" => {
+                let error = &event["error"];
+                // Server VAD already committed everything; nothing left to do
+                if error["code"].as_str() == Some("input_audio_buffer_commit_empty") {
+                    return Ok(Some(ServerEvent::Committed));
+                }
+                let message = error["message"].as_str().unwrap_or("Unknown error");
+                return Err(anyhow::anyhow!("{PROVIDER} error: {message}").into());
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    /// Find a segment, adding it if it's new
+    fn item(&mut self, item_id: &str) -> &mut Item {
+        let index = match self.items.iter().position(|item| item.id == item_id) {
+            Some(index) => index,
+            None => {
+                self.items.push(Item {
+                    id: item_id.to_string(),
+                    text: String::new(),
+                    done: false,
+                });
+                self.items.len() - 1
+            }
+        };
+        &mut self.items[index]
+    }
+}
+
+/// Configure the session for transcription with server-side speech detection
+fn session_update(config: &RealtimeConfig) -> Value {
+    let mut transcription = json!({ "model": config.model });
+    if let Some(language) = &config.language {
+        transcription["language"] = json!(language);
+    }
+    if let Some(prompt) = &config.prompt {
+        transcription["prompt"] = json!(prompt);
+    }
+    json!({
+        "type": "transcription_session.update",
+        "session": {
+            "input_audio_format": "pcm16",
+            "input_audio_transcription": transcription,
+            "turn_detection": { "type": "server_vad" },
+        }
+    })
+}
+
+/// Encode 24 kHz mono samples as a base64 PCM16 append message
+fn append_message(samples: &[f32]) -> Value {
+    let bytes: Vec<u8> = samples
+        .iter()
+        .flat_map(|&s| ((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes())
+        .collect();
+    json!({
+        "type": "input_audio_buffer.append",
+        "audio": base64::engine::general_purpose::STANDARD.encode(bytes),
+    })
+}
+
+async fn send_json<S>(sink: &mut S, value: &Value) -> Result<()>
+where
+    S: futures_util::Sink<Message, Error = tungstenite::Error> + Unpin,
+{
+    sink.send(Message::text(value.to_string()))
+        .await
+        .map_err(socket_error)
+}
+
+/// Classify a failed WebSocket handshake
+fn connect_error(err: tungstenite::Error) -> WhisError {
+    if let tungstenite::Error::Http(response) = &err {
+        let status = response.status();
+        let message = response
+            .body()
+            .as_ref()
+            .map(|body| String::from_utf8_lossy(body).to_string())
+            .unwrap_or_default();
+        return match status.as_u16() {
+            401 | 403 => WhisError::Authentication {
+                provider: PROVIDER,
+                message,
+            },
+            429 if message.contains("insufficient_quota") => WhisError::QuotaExceeded {
+                provider: PROVIDER,
+                message,
+            },
+            429 => WhisError::RateLimited {
+                provider: PROVIDER,
+                message,
+                retry_after: None,
+            },
+            status => WhisError::Api {
+                provider: PROVIDER,
+                status,
+                message,
+                retry_after: None,
+            },
+        };
+    }
+    socket_error(err)
+}
+
+fn socket_error(err: tungstenite::Error) -> WhisError {
+    WhisError::Network {
+        provider: PROVIDER,
+        message: err.to_string(),
+    }
+}
+
+fn closed_error() -> WhisError {
+    WhisError::Network {
+        provider: PROVIDER,
+        message: "connection closed by server".to_string(),
+    }
+}
@@ -0,0 +1,247 @@
+//! Retry transient provider failures with jittered exponential backoff
+
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::future::Future;
+use std::time::Duration;
+
+use crate::error::{Result, WhisError};
+
+/// `Retry-After` values are capped at this many seconds, so a server can't stall a retry for long
+const MAX_RETRY_AFTER_SECS: u64 = 120;
+
+/// How often and how long to retry
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled on every further attempt
+    pub base_delay: Duration,
+    /// Upper bound for the backoff delay
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `attempt` (1-based). `Retry-After` wins over the
+    /// backoff; otherwise a random delay between half and the full backoff is used
+    /// so parallel chunks don't retry in lockstep.
+    fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(delay) = retry_after {
+            return delay;
+        }
+        let backoff = self
+            .base_delay
+            .saturating_mul(1 << (attempt - 1).min(16))
+            .min(self.max_delay);
+        backoff.mul_f64(0.5 + fastrand::f64() * 0.5)
+    }
+
+    /// If `err` is worth retrying after `attempt` failed attempts, the delay to wait
+    fn next_delay(&self, attempt: u32, err: &WhisError) -> Option<Duration> {
+        if attempt >= self.max_attempts || !err.is_retryable() {
+            return None;
+        }
+        Some(self.delay(attempt, err.retry_after()))
+    }
+}
+
+/// Parse `Retry-After` given in seconds, capped at two minutes (HTTP dates are ignored)
+pub fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let secs: u64 = headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(secs.min(MAX_RETRY_AFTER_SECS)))
+}
+
+/// Run `operation` until it succeeds, fails with a non-retryable error,
+/// or the policy runs out of attempts. `on_retry` hears about each failed attempt
+/// that is retried: its number, the error and the delay before the next one.
+pub async fn retry_async<T, F, Fut>(
+    policy: &RetryPolicy,
+    mut operation: F,
+    on_retry: impl Fn(u32, &WhisError, Duration),
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Ok(value) => return Ok(value),
+            Err(err) => {
+                let Some(delay) = policy.next_delay(attempt, &err) else {
+                    return Err(err);
+                };
+                on_retry(attempt, &err, delay);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+    use std::cell::Cell;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+
+    fn api_error(status: u16) -> WhisError {
+        WhisError::Api {
+            provider: "test",
+            status,
+            message: String::new(),
+            retry_after: None,
+        }
+    }
+
+    fn retry_after(value: &str) -> Option<Duration> {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(value).unwrap());
+        parse_retry_after(&headers)
+    }
+
+    #[test]
+    fn test_delay_doubles_within_jitter_bounds() {
+        let policy = policy();
+        for (attempt, backoff_secs) in [(1, 1.0), (2, 2.0), (3, 4.0), (4, 8.0)] {
+            for _ in 0..100 {
+                let delay = policy.delay(attempt, None).as_secs_f64();
+                assert!(
+                    (backoff_secs / 2.0..=backoff_secs).contains(&delay),
+                    "attempt {attempt}: {delay}s"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_delay_is_capped() {
+        let policy = policy();
+        for attempt in [5, 10, 40] {
+            let delay = policy.delay(attempt, None);
+            assert!(delay <= policy.max_delay, "attempt {attempt}: {delay:?}");
+            assert!(
+                delay >= policy.max_delay / 2,
+                "attempt {attempt}: {delay:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_retry_after_wins_over_backoff() {
+        let delay = policy().delay(3, Some(Duration::from_secs(7)));
+        assert_eq!(delay, Duration::from_secs(7));
+    }
+
+    #[test]
+    fn test_next_delay_stops_at_max_attempts_and_permanent_errors() {
+        let policy = policy();
+        assert!(policy.next_delay(4, &api_error(503)).is_some());
+        assert!(policy.next_delay(5, &api_error(503)).is_none());
+        assert!(policy.next_delay(1, &api_error(400)).is_none());
+    }
+
+    #[test]
+    fn test_parse_retry_after_seconds() {
+        assert_eq!(retry_after("5"), Some(Duration::from_secs(5)));
+        assert_eq!(retry_after(" 0 "), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn test_parse_retry_after_caps_long_waits() {
+        assert_eq!(
+            retry_after("86400"),
+            Some(Duration::from_secs(MAX_RETRY_AFTER_SECS))
+        );
+    }
+
+    #[test]
+    fn test_parse_retry_after_ignores_dates_and_garbage() {
+        assert_eq!(retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+        assert_eq!(retry_after("soon"), None);
+        assert_eq!(retry_after("-1"), None);
+        assert_eq!(parse_retry_after(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(api_error(408).is_retryable());
+        assert!(api_error(500).is_retryable());
+        assert!(api_error(503).is_retryable());
+        assert!(!api_error(400).is_retryable());
+        assert!(!api_error(404).is_retryable());
+        assert!(
+            WhisError::RateLimited {
+                provider: "test",
+                message: String::new(),
+                retry_after: None,
+            }
+            .is_retryable()
+        );
+        assert!(
+            !WhisError::QuotaExceeded {
+                provider: "test",
+                message: String::new(),
+            }
+            .is_retryable()
+        );
+        assert!(
+            !WhisError::Authentication {
+                provider: "test",
+                message: String::new(),
+            }
+            .is_retryable()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_retry_async_retries_until_success() {
+        let policy = RetryPolicy {
+            base_delay: Duration::ZERO,
+            ..policy()
+        };
+        let calls = Cell::new(0);
+        let retries = Cell::new(0);
+        let result = retry_async(
+            &policy,
+            || {
+                calls.set(calls.get() + 1);
+                let call = calls.get();
+                async move {
+                    if call < 3 {
+                        Err(api_error(503))
+                    } else {
+                        Ok(call)
+                    }
+                }
+            },
+            |_, _, _| retries.set(retries.get() + 1),
+        )
+        .await;
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(retries.get(), 2);
+    }
+}
@@ -1,25 +1,137 @@
-use anyhow::Result;
+use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::encoder::{AudioEncoder, EncodingProfile};
+use crate::error::Result;
+use crate::vad::VadAggressiveness;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub shortcut: String,
+    /// Shortcut that cancels a transcription in progress
+    #[serde(default = "default_cancel_shortcut")]
+    pub cancel_shortcut: String,
+    /// Shortcut that throws away the current recording without transcribing
+    #[serde(default = "default_discard_shortcut")]
+    pub discard_shortcut: String,
+    /// Shortcut that pauses or resumes the current recording
+    #[serde(default = "default_pause_shortcut")]
+    pub pause_shortcut: String,
     #[serde(default)]
     pub openai_api_key: Option<String>,
+    /// Base URL of an OpenAI-compatible server (e.g. "http://localhost:8000/v1")
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// Transcription model name (defaults to the provider's model)
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Spoken language as ISO-639-1 code (auto-detected when unset)
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Prompt to guide style or spelling of names and jargon
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Sampling temperature between 0 and 1
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// Local GGML model name (e.g. "base.en") or path; when set, transcription runs offline
+    #[serde(default)]
+    pub local_model: Option<String>,
+    /// Directory for downloaded models (defaults to ~/.local/share/whis/models)
+    #[serde(default)]
+    pub models_dir: Option<PathBuf>,
+    /// Input device name; the default device is used when unset or not connected
+    #[serde(default)]
+    pub input_device: Option<String>,
+    /// Encoder used to compress recordings before upload
+    #[serde(default)]
+    pub encoder: AudioEncoder,
+    /// Sample rate, channels and bitrate used for uploads
+    #[serde(default)]
+    pub encoding_profile: EncodingProfile,
+    /// How eagerly silence is cut before transcription
+    #[serde(default)]
+    pub vad: VadAggressiveness,
+    /// Stop recording after this many seconds of silence following speech (off when unset)
+    #[serde(default)]
+    pub auto_stop_secs: Option<f32>,
+    /// Input level in dBFS below which audio counts as silence for auto-stop
+    #[serde(default = "default_silence_threshold_db")]
+    pub silence_threshold_db: f32,
+    /// Suppress steady background noise before transcription
+    #[serde(default)]
+    pub denoise: bool,
+    /// Filter out rumble and hum below 80 Hz before transcription
+    #[serde(default)]
+    pub high_pass: bool,
+    /// Normalize the loudness of recordings before transcription
+    #[serde(default)]
+    pub normalize: bool,
+    /// Keep the microphone open and put this many milliseconds of audio from before
+    /// the hotkey press in front of each recording ("warm mic"; off when unset)
+    #[serde(default)]
+    pub pre_roll_ms: Option<u32>,
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
             shortcut: "Ctrl+Shift+R".to_string(),
+            cancel_shortcut: default_cancel_shortcut(),
+            discard_shortcut: default_discard_shortcut(),
+            pause_shortcut: default_pause_shortcut(),
             openai_api_key: None,
+            base_url: None,
+            model: None,
+            language: None,
+            prompt: None,
+            temperature: None,
+            local_model: None,
+            models_dir: None,
+            input_device: None,
+            encoder: AudioEncoder::default(),
+            encoding_profile: EncodingProfile::default(),
+            vad: VadAggressiveness::default(),
+            auto_stop_secs: None,
+            silence_threshold_db: default_silence_threshold_db(),
+            denoise: false,
+            high_pass: false,
+            normalize: false,
+            pre_roll_ms: None,
         }
     }
 }
 
+fn default_cancel_shortcut() -> String {
+    "Ctrl+Shift+X".to_string()
+}
+
+fn default_discard_shortcut() -> String {
+    "Ctrl+Shift+D".to_string()
+}
+
+fn default_pause_shortcut() -> String {
+    "Ctrl+Shift+P".to_string()
+}
+
+fn default_silence_threshold_db() -> f32 {
+    -40.0
+}
+
+/// Longest pre-roll the warm mic keeps, in milliseconds
+pub const MAX_PRE_ROLL_MS: u32 = 2000;
+
 impl Settings {
+    /// Audio to keep from before each recording, or `None` when the warm mic is off
+    pub fn pre_roll(&self) -> Option<Duration> {
+        self.pre_roll_ms
+            .filter(|ms| *ms > 0)
+            .map(|ms| Duration::from_millis(ms.min(MAX_PRE_ROLL_MS) as u64))
+    }
+
     /// Get the settings file path (~/.config/whis/settings.json)
     pub fn path() -> PathBuf {
         dirs::config_dir()
@@ -31,10 +143,10 @@ impl Settings {
     /// Load settings from disk
     pub fn load() -> Self {
         let path = Self::path();
-        if let Ok(content) = fs::read_to_string(&path) {
-            if let Ok(settings) = serde_json::from_str(&content) {
-                return settings;
-            }
+        if let Ok(content) = fs::read_to_string(&path)
+            && let Ok(settings) = serde_json::from_str(&content)
+        {
+            return settings;
         }
         Self::default()
     }
@@ -43,15 +155,17 @@ impl Settings {
     pub fn save(&self) -> Result<()> {
         let path = Self::path();
         if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent)?;
+            fs::create_dir_all(parent).context("Failed to create config directory")?;
         }
-        let content = serde_json::to_string_pretty(self)?;
-        fs::write(&path, &content)?;
+        let content = serde_json::to_string_pretty(self).context("Failed to serialize settings")?;
+        fs::write(&path, &content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
 
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
-            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))?;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+                .context("Failed to set settings file permissions")?;
         }
 
         Ok(())
@@ -0,0 +1,419 @@
+//! Where recordings get their audio
+//!
+//! [`AudioRecorder`](crate::AudioRecorder) reads from an [`AudioSource`]: normally a
+//! microphone through cpal, but an audio file or a generated test tone work just as
+//! well. The latter two run on machines without a sound card, so the whole
+//! record-and-transcribe flow can be exercised in CI and headless containers by
+//! setting [`SOURCE_ENV`] (e.g. `WHIS_AUDIO_SOURCE=file:speech.wav`).
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use std::env;
+use std::f32::consts::TAU;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::decode::decode_file;
+use crate::error::{Result, WhisError};
+use crate::settings::Settings;
+
+/// Environment variable that replaces the microphone: `file:<path>` plays an audio
+/// file, `tone` or `tone:<hz>` a sine tone, and `mic:<device>` picks an input device
+pub const SOURCE_ENV: &str = "WHIS_AUDIO_SOURCE";
+/// Frequency of `tone` without a number
+const DEFAULT_TONE_HZ: f32 = 440.0;
+/// Level of generated tones, loud enough to count as speech for auto-stop
+const TONE_AMPLITUDE: f32 = 0.25;
+/// How much audio generated sources deliver at a time, like a device buffer
+const BLOCK: Duration = Duration::from_millis(20);
+
+/// Receives interleaved samples from a source as they arrive
+pub type SampleSink = Box<dyn FnMut(Vec<f32>) + Send>;
+
+/// A source of live audio for recordings
+pub trait AudioSource: Send {
+    /// Start delivering audio to `sink` until the returned stream is dropped
+    fn open(&self, sink: SampleSink) -> Result<InputStream>;
+}
+
+/// The source recordings come from: the one in [`SOURCE_ENV`] if set, otherwise the
+/// input device from settings
+pub fn from_settings(settings: &Settings) -> Result<Box<dyn AudioSource>> {
+    match env::var(SOURCE_ENV) {
+        Ok(spec) if !spec.trim().is_empty() => parse(&spec),
+        _ => Ok(Box::new(MicrophoneSource::new(
+            settings.input_device.clone(),
+        ))),
+    }
+}
+
+/// Parse a source as written in [`SOURCE_ENV`]
+pub fn parse(spec: &str) -> Result<Box<dyn AudioSource>> {
+    let (kind, arg) = match spec.split_once(':') {
+        Some((kind, arg)) => (kind, Some(arg)),
+        None => (spec, None),
+    };
+    let source: Box<dyn AudioSource> = match (kind.trim(), arg) {
+        ("mic", device) => Box::new(MicrophoneSource::new(
+            device.filter(|device| !device.is_empty()).map(String::from),
+        )),
+        ("file", Some(path)) if !path.is_empty() => Box::new(FileSource::new(path)),
+        ("tone", None) => Box::new(ToneSource::new(DEFAULT_TONE_HZ)),
+        ("tone", Some(hz)) => match hz.trim().parse::<f32>() {
+            Ok(hz) if hz.is_finite() && hz > 0.0 => Box::new(ToneSource::new(hz)),
+            _ => {
+                return Err(WhisError::AudioInput(format!(
+                    "'{hz}' is not a tone frequency in Hz"
+                )));
+            }
+        },
+        _ => {
+            return Err(WhisError::AudioInput(format!(
+                "Unknown audio source '{spec}' (expected file:<path>, tone[:<hz>] or mic[:<device>])"
+            )));
+        }
+    };
+    Ok(source)
+}
+
+/// Audio flowing from an [`AudioSource`]. Dropping it stops the audio and releases the
+/// device.
+pub struct InputStream {
+    sample_rate: u32,
+    channels: u16,
+    _handle: StreamHandle,
+}
+
+/// Keeps the audio of an [`InputStream`] going
+enum StreamHandle {
+    Device { _stream: cpal::Stream },
+    Generated { _generator: Generator },
+}
+
+impl InputStream {
+    /// Deliver a generated signal in real time from a background thread. `fill` writes
+    /// the next block of interleaved samples each time.
+    pub fn generated(
+        sample_rate: u32,
+        channels: u16,
+        fill: impl FnMut(&mut [f32]) + Send + 'static,
+        sink: SampleSink,
+    ) -> Self {
+        Self {
+            sample_rate,
+            channels,
+            _handle: StreamHandle::Generated {
+                _generator: Generator::spawn(sample_rate, channels, fill, sink),
+            },
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+}
+
+/// Background thread behind a generated [`InputStream`]
+struct Generator {
+    stop: Arc<AtomicBool>,
+    thread: Option<thread::JoinHandle<()>>,
+}
+
+impl Generator {
+    fn spawn(
+        sample_rate: u32,
+        channels: u16,
+        mut fill: impl FnMut(&mut [f32]) + Send + 'static,
+        mut sink: SampleSink,
+    ) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let block_len =
+            ((sample_rate as f64 * BLOCK.as_secs_f64()) as usize).max(1) * channels as usize;
+        let thread = thread::spawn({
+            let stop = stop.clone();
+            move || {
+                let start = Instant::now();
+                let mut blocks = 0;
+                while !stop.load(Ordering::Relaxed) {
+                    let mut block = vec![0.0; block_len];
+                    fill(&mut block);
+                    sink(block);
+                    blocks += 1;
+                    // Keep to real time, like a device: the next block is due once this one played
+                    if let Some(wait) = (BLOCK * blocks).checked_sub(start.elapsed()) {
+                        thread::sleep(wait);
+                    }
+                }
+            }
+        });
+        Self {
+            stop,
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for Generator {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Records from an input device of the default audio host
+#[derive(Debug, Clone, Default)]
+pub struct MicrophoneSource {
+    /// Device name; `None` records from the default device
+    device_name: Option<String>,
+}
+
+impl MicrophoneSource {
+    /// Record from the named device, falling back to the default one if it isn't connected
+    pub fn new(device_name: Option<String>) -> Self {
+        Self { device_name }
+    }
+}
+
+impl AudioSource for MicrophoneSource {
+    fn open(&self, sink: SampleSink) -> Result<InputStream> {
+        let host = cpal::default_host();
+        let device = find_input_device(&host, self.device_name.as_deref())?;
+
+        let config = device
+            .default_input_config()
+            .map_err(|e| WhisError::AudioInput(e.to_string()))?;
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels();
+
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config.into(), sink)?,
+            cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config.into(), sink)?,
+            cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config.into(), sink)?,
+            format => {
+                return Err(WhisError::AudioInput(format!(
+                    "Unsupported sample format: {format}"
+                )));
+            }
+        };
+
+        stream
+            .play()
+            .map_err(|e| WhisError::AudioInput(e.to_string()))?;
+
+        Ok(InputStream {
+            sample_rate,
+            channels,
+            _handle: StreamHandle::Device { _stream: stream },
+        })
+    }
+}
+
+/// Find an input device by name, falling back to the default device when it's
+/// missing (e.g. an unplugged headset)
+fn find_input_device(host: &cpal::Host, name: Option<&str>) -> Result<cpal::Device> {
+    if let Some(name) = name {
+        let found = host
+            .input_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|device| device.name().is_ok_and(|n| n == name)));
+        match found {
+            Some(device) => return Ok(device),
+            None => eprintln!("Input device '{name}' not found, using the default device"),
+        }
+    }
+    host.default_input_device().ok_or(WhisError::NoInputDevice)
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mut sink: SampleSink,
+) -> Result<cpal::Stream>
+where
+    T: cpal::Sample + cpal::SizedSample,
+    f32: cpal::FromSample<T>,
+{
+    let err_fn = |err| eprintln!("Error in audio stream: {err}");
+
+    let stream = device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                sink(
+                    data.iter()
+                        .map(|&sample| cpal::Sample::from_sample(sample))
+                        .collect(),
+                );
+            },
+            err_fn,
+            None,
+        )
+        .map_err(|e| WhisError::AudioInput(e.to_string()))?;
+
+    Ok(stream)
+}
+
+/// Plays an audio file (WAV, MP3, FLAC, ...) in real time as if it were spoken into a
+/// microphone, then continues with silence until the recording stops. Every recording
+/// starts again from the beginning of the file.
+#[derive(Debug, Clone)]
+pub struct FileSource {
+    path: PathBuf,
+}
+
+impl FileSource {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl AudioSource for FileSource {
+    fn open(&self, sink: SampleSink) -> Result<InputStream> {
+        let (samples, sample_rate, channels) = decode_file(&self.path)?.into_parts();
+        let mut position = 0;
+        let fill = move |block: &mut [f32]| {
+            let available = block.len().min(samples.len() - position);
+            block[..available].copy_from_slice(&samples[position..position + available]);
+            position += available;
+        };
+        Ok(InputStream::generated(sample_rate, channels, fill, sink))
+    }
+}
+
+/// Generates a steady sine tone, for checking the recording pipeline without any input
+#[derive(Debug, Clone, Copy)]
+pub struct ToneSource {
+    frequency: f32,
+    sample_rate: u32,
+    channels: u16,
+}
+
+impl ToneSource {
+    /// A tone at `frequency` Hz, as 16 kHz mono
+    pub fn new(frequency: f32) -> Self {
+        Self {
+            frequency,
+            sample_rate: 16000,
+            channels: 1,
+        }
+    }
+
+    /// Generate at another sample rate or channel count, like a device would deliver
+    pub fn with_format(self, sample_rate: u32, channels: u16) -> Self {
+        Self {
+            sample_rate,
+            channels,
+            ..self
+        }
+    }
+}
+
+impl AudioSource for ToneSource {
+    fn open(&self, sink: SampleSink) -> Result<InputStream> {
+        if self.sample_rate == 0 || self.channels == 0 {
+            return Err(WhisError::AudioInput(
+                "Tone needs a sample rate and at least one channel".to_string(),
+            ));
+        }
+        let step = self.frequency / self.sample_rate as f32;
+        let channels = self.channels as usize;
+        let mut phase = 0.0f32;
+        let fill = move |block: &mut [f32]| {
+            for frame in block.chunks_exact_mut(channels) {
+                frame.fill(TONE_AMPLITUDE * (TAU * phase).sin());
+                phase = (phase + step).fract();
+            }
+        };
+        Ok(InputStream::generated(
+            self.sample_rate,
+            self.channels,
+            fill,
+            sink,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    /// Open `source` and collect the first `len` samples it delivers
+    fn collect(source: &impl AudioSource, len: usize) -> (Vec<f32>, u32, u16) {
+        let (tx, rx) = mpsc::channel();
+        let stream = source
+            .open(Box::new(move |block| {
+                let _ = tx.send(block);
+            }))
+            .unwrap();
+        let mut samples = Vec::new();
+        while samples.len() < len {
+            // Only guards against a hang; the test doesn't depend on timing
+            let block = rx
+                .recv_timeout(Duration::from_secs(10))
+                .expect("source stopped delivering audio");
+            samples.extend(block);
+        }
+        samples.truncate(len);
+        (samples, stream.sample_rate(), stream.channels())
+    }
+
+    #[test]
+    fn test_tone_source_delivers_tone_in_requested_format() {
+        let source = ToneSource::new(440.0).with_format(48000, 2);
+        let (samples, sample_rate, channels) = collect(&source, 48000 * 2 / 10);
+        assert_eq!((sample_rate, channels), (48000, 2));
+        // Both channels carry the same tone
+        assert!(samples.chunks(2).all(|frame| frame[0] == frame[1]));
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((peak - TONE_AMPLITUDE).abs() < 0.01, "peak {peak}");
+    }
+
+    #[test]
+    fn test_file_source_plays_file_then_silence() {
+        let path = env::temp_dir().join(format!("whis-source-{}.wav", std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..800 {
+            writer.write_sample(i16::MAX / 2).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let (samples, sample_rate, channels) = collect(&FileSource::new(&path), 1600);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!((sample_rate, channels), (8000, 1));
+        assert!(samples[..800].iter().all(|s| (s - 0.5).abs() < 0.01));
+        assert!(samples[800..].iter().all(|s| *s == 0.0));
+    }
+
+    #[test]
+    fn test_tone_source_rejects_empty_format() {
+        let source = ToneSource::new(440.0).with_format(16000, 0);
+        assert!(source.open(Box::new(|_| {})).is_err());
+    }
+
+    #[test]
+    fn test_parse_sources() {
+        assert!(parse("tone").is_ok());
+        assert!(parse("tone:1000").is_ok());
+        assert!(parse("file:/tmp/speech.wav").is_ok());
+        assert!(parse("mic").is_ok());
+        assert!(parse("mic:USB Microphone").is_ok());
+        assert!(parse("tone:loud").is_err());
+        assert!(parse("file:").is_err());
+        assert!(parse("speaker").is_err());
+    }
+}
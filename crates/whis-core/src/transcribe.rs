@@ -1,273 +1,520 @@
-use anyhow::{Context, Result};
-use reqwest::blocking::multipart;
-use serde::Deserialize;
-use std::sync::Arc;
-use tokio::sync::Semaphore;
-
-use crate::audio::AudioChunk;
-
-/// Maximum concurrent API requests to OpenAI
+use anyhow::Context;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::{Semaphore, mpsc};
+use tokio_util::sync::CancellationToken;
+
+use crate::audio::{AudioChunk, RecordingData, RecordingOutput};
+use crate::encoder::{EncodingOptions, mime_type};
+use crate::error::{Result, WhisError};
+use crate::provider::{TranscriptionOptions, TranscriptionProvider, TranscriptionRequest};
+use crate::retry::{RetryPolicy, retry_async};
+use crate::transcript::Transcript;
+
+/// Maximum concurrent API requests to the provider
 const MAX_CONCURRENT_REQUESTS: usize = 3;
-/// Maximum words to search for overlap between chunks
-const MAX_OVERLAP_WORDS: usize = 15;
 /// API request timeout in seconds
 const API_TIMEOUT_SECS: u64 = 300;
 
-#[derive(Deserialize, Debug)]
-struct TranscriptionResponse {
-    text: String,
-}
-
 /// Result of transcribing a single chunk
 pub struct ChunkTranscription {
     pub index: usize,
-    pub text: String,
-    pub has_leading_overlap: bool,
+    /// Transcript with timestamps relative to the start of the chunk
+    pub transcript: Transcript,
+    /// Start of the chunk in the recording, in seconds
+    pub offset_secs: f64,
 }
 
-/// Transcribe a single audio file (blocking, for simple single-file case)
-pub fn transcribe_audio(api_key: &str, audio_data: Vec<u8>) -> Result<String> {
-    let client = reqwest::blocking::Client::builder()
+/// Outcome of transcribing a stream of chunks, see [`transcribe_chunks_partial`]
+pub(crate) struct ChunkResults {
+    /// Transcribed chunks in chunk order
+    pub transcribed: Vec<ChunkTranscription>,
+    /// Index and error of each chunk that failed
+    pub failed: Vec<(usize, WhisError)>,
+}
+
+/// What a transcription in progress reports through its [`ProgressCallback`]
+#[derive(Debug)]
+pub enum Progress<'a> {
+    /// `completed` of the `total` chunks received so far are transcribed
+    Chunk { completed: usize, total: usize },
+    /// A request failed and is sent again after `delay`
+    Retry {
+        /// 1-based index of the chunk, `None` when the recording is sent in one piece
+        chunk: Option<usize>,
+        /// The attempt that failed (1 for the first)
+        attempt: u32,
+        error: &'a WhisError,
+        delay: Duration,
+    },
+}
+
+/// Receives [`Progress`] reports, e.g. to show them in a status line
+pub type ProgressCallback = Box<dyn Fn(Progress) + Send + Sync>;
+
+/// Shared HTTP client, so connections are pooled across requests and recordings
+fn http_client() -> Result<reqwest::Client> {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    if let Some(client) = CLIENT.get() {
+        return Ok(client.clone());
+    }
+    let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(API_TIMEOUT_SECS))
         .build()
         .context("Failed to create HTTP client")?;
+    Ok(CLIENT.get_or_init(|| client).clone())
+}
 
-    let form = multipart::Form::new().text("model", "whisper-1").part(
-        "file",
-        multipart::Part::bytes(audio_data)
-            .file_name("audio.mp3")
-            .mime_str("audio/mpeg")?,
-    );
-
-    let response = client
-        .post("https://api.openai.com/v1/audio/transcriptions")
-        .header("Authorization", format!("Bearer {api_key}"))
-        .multipart(form)
-        .send()
-        .context("Failed to send request to OpenAI API")?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response
-            .text()
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        anyhow::bail!("OpenAI API error ({status}): {error_text}");
+/// Run `future` unless `cancel` fires first, in which case it is dropped
+/// (aborting any HTTP request in flight) and [`WhisError::Cancelled`] is returned
+pub async fn cancellable<T>(
+    cancel: &CancellationToken,
+    future: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    tokio::select! {
+        biased;
+        _ = cancel.cancelled() => Err(WhisError::Cancelled),
+        result = future => result,
     }
+}
 
-    let text = response.text().context("Failed to get response text")?;
-    let transcription: TranscriptionResponse =
-        serde_json::from_str(&text).context("Failed to parse OpenAI API response")?;
+/// Transcribe a finalized recording, whatever shape `finalize_for` produced it in.
+///
+/// Cancelling `cancel` aborts in-flight requests and pending retries. Local
+/// inference can't be interrupted; it finishes in the background and its
+/// result is discarded.
+pub async fn transcribe(
+    provider: Arc<dyn TranscriptionProvider>,
+    output: RecordingOutput,
+    options: &TranscriptionOptions,
+    progress_callback: Option<ProgressCallback>,
+    cancel: &CancellationToken,
+) -> Result<Transcript> {
+    match output {
+        RecordingOutput::Single { data, extension } => {
+            let client = http_client()?;
+            let request = TranscriptionRequest {
+                audio_data: data,
+                filename: format!("audio.{extension}"),
+                mime_type: mime_type(extension),
+                options: options.clone(),
+            };
+            // Each attempt sends its own copy, the request body is consumed on send
+            let policy = RetryPolicy::default();
+            let attempts = retry_async(
+                &policy,
+                || provider.transcribe(&client, request.clone()),
+                |attempt, error, delay| {
+                    if let Some(cb) = &progress_callback {
+                        cb(Progress::Retry {
+                            chunk: None,
+                            attempt,
+                            error,
+                            delay,
+                        });
+                    }
+                },
+            );
+            cancellable(cancel, attempts).await
+        }
+        RecordingOutput::Chunked(chunks) => {
+            parallel_transcribe(provider, chunks, options, progress_callback, cancel).await
+        }
+        RecordingOutput::Pcm(samples) => {
+            // Local inference is CPU-bound, keep it off the async runtime
+            let options = options.clone();
+            let task =
+                tokio::task::spawn_blocking(move || provider.transcribe_pcm(samples, &options));
+            cancellable(cancel, async { task.await.context("Failed to join task")? }).await
+        }
+    }
+}
+
+/// Transcribe a complete recording, such as a decoded file: clean it up, trim
+/// silence, encode it the way the provider reads it (chunked if it is long) and
+/// transcribe. Timestamps refer to the recording as given.
+pub async fn transcribe_recording(
+    provider: Arc<dyn TranscriptionProvider>,
+    recording: RecordingData,
+    options: &TranscriptionOptions,
+    encoding: EncodingOptions,
+    progress_callback: Option<ProgressCallback>,
+    cancel: &CancellationToken,
+) -> Result<Transcript> {
+    // Resampling and encoding are CPU-bound, keep them off the async runtime
+    let pcm = provider.accepts_pcm();
+    let prepare = tokio::task::spawn_blocking(move || {
+        let (recording, time_map) = recording
+            .preprocess(&encoding.preprocess)?
+            .trim_silence(encoding.vad);
+        let output = if pcm {
+            recording.finalize_pcm()?
+        } else {
+            recording.finalize(&encoding)?
+        };
+        Ok::<_, WhisError>((output, time_map))
+    });
+    let (output, time_map) = cancellable(cancel, async {
+        prepare.await.context("Failed to join task")?
+    })
+    .await?;
+
+    let mut transcript = transcribe(provider, output, options, progress_callback, cancel).await?;
+    time_map.restore(&mut transcript);
+    Ok(transcript)
+}
+
+/// Blocking wrapper around [`transcribe`] for callers without an async runtime.
+/// Must not be called from within one.
+pub fn transcribe_blocking(
+    provider: Arc<dyn TranscriptionProvider>,
+    output: RecordingOutput,
+    options: &TranscriptionOptions,
+) -> Result<Transcript> {
+    // One runtime for all blocking calls: pooled connections are bound to the
+    // runtime that opened them and break if it is dropped
+    static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+    if RUNTIME.get().is_none() {
+        let runtime = tokio::runtime::Runtime::new().context("Failed to create async runtime")?;
+        let _ = RUNTIME.set(runtime);
+    }
+    RUNTIME.get().unwrap().block_on(transcribe(
+        provider,
+        output,
+        options,
+        None,
+        &CancellationToken::new(),
+    ))
+}
 
-    Ok(transcription.text)
+/// Transcribe a single MP3 file (blocking)
+pub fn transcribe_audio(
+    provider: Arc<dyn TranscriptionProvider>,
+    audio_data: Vec<u8>,
+    options: &TranscriptionOptions,
+) -> Result<Transcript> {
+    let output = RecordingOutput::Single {
+        data: audio_data,
+        extension: "mp3",
+    };
+    transcribe_blocking(provider, output, options)
 }
 
 /// Transcribe a single chunk asynchronously
 async fn transcribe_chunk_async(
     client: &reqwest::Client,
-    api_key: &str,
+    provider: &dyn TranscriptionProvider,
     chunk: AudioChunk, // Take ownership to avoid clone
+    options: &TranscriptionOptions,
+    progress_callback: Option<&ProgressCallback>,
 ) -> Result<ChunkTranscription> {
     let chunk_index = chunk.index;
-    let has_leading_overlap = chunk.has_leading_overlap;
-
-    let form = reqwest::multipart::Form::new()
-        .text("model", "whisper-1")
-        .part(
-            "file",
-            reqwest::multipart::Part::bytes(chunk.data) // No clone needed
-                .file_name(format!("audio_chunk_{chunk_index}.mp3"))
-                .mime_str("audio/mpeg")?,
-        );
-
-    let response = client
-        .post("https://api.openai.com/v1/audio/transcriptions")
-        .header("Authorization", format!("Bearer {api_key}"))
-        .multipart(form)
-        .send()
-        .await
-        .context("Failed to send request to OpenAI API")?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        anyhow::bail!("OpenAI API error ({status}): {error_text}");
-    }
-
-    let text = response
-        .text()
-        .await
-        .context("Failed to get response text")?;
-    let transcription: TranscriptionResponse =
-        serde_json::from_str(&text).context("Failed to parse OpenAI API response")?;
+    let offset_secs = chunk.offset_secs;
+
+    let request = TranscriptionRequest {
+        audio_data: chunk.data, // No clone needed
+        filename: format!("audio_chunk_{chunk_index}.{}", chunk.extension),
+        mime_type: mime_type(chunk.extension),
+        options: options.clone(),
+    };
+    let transcript = retry_async(
+        &RetryPolicy::default(),
+        || provider.transcribe(client, request.clone()),
+        |attempt, error, delay| {
+            if let Some(cb) = progress_callback {
+                cb(Progress::Retry {
+                    chunk: Some(chunk_index + 1),
+                    attempt,
+                    error,
+                    delay,
+                });
+            }
+        },
+    )
+    .await?;
 
     Ok(ChunkTranscription {
         index: chunk_index,
-        text: transcription.text,
-        has_leading_overlap,
+        transcript,
+        offset_secs,
     })
 }
 
-/// Transcribe multiple chunks in parallel with rate limiting
+/// Transcribe multiple chunks in parallel with rate limiting.
+/// Cancelling `cancel` aborts all chunk requests, running or queued.
 pub async fn parallel_transcribe(
-    api_key: &str,
+    provider: Arc<dyn TranscriptionProvider>,
     chunks: Vec<AudioChunk>,
-    progress_callback: Option<Box<dyn Fn(usize, usize) + Send + Sync>>,
-) -> Result<String> {
-    let total_chunks = chunks.len();
+    options: &TranscriptionOptions,
+    progress_callback: Option<ProgressCallback>,
+    cancel: &CancellationToken,
+) -> Result<Transcript> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    for chunk in chunks {
+        let _ = tx.send(chunk);
+    }
+    drop(tx);
 
-    // Create shared HTTP client with timeout
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(API_TIMEOUT_SECS))
-        .build()
-        .context("Failed to create HTTP client")?;
+    let results = transcribe_chunks(provider, rx, options, progress_callback, cancel).await?;
+    Ok(merge_transcriptions(results))
+}
 
-    // Semaphore to limit concurrent requests
-    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
-    let client = Arc::new(client);
-    let api_key = Arc::new(api_key.to_string());
-    let completed = Arc::new(std::sync::atomic::AtomicUsize::new(0));
-    let progress_callback = progress_callback.map(Arc::new);
+/// Transcribe chunks as they arrive on `chunks`, a few at a time, until the channel
+/// closes. Results are in chunk order, with timestamps relative to each chunk.
+/// Progress reports count the chunks received so far as the total.
+pub(crate) async fn transcribe_chunks(
+    provider: Arc<dyn TranscriptionProvider>,
+    chunks: mpsc::UnboundedReceiver<AudioChunk>,
+    options: &TranscriptionOptions,
+    progress_callback: Option<ProgressCallback>,
+    cancel: &CancellationToken,
+) -> Result<Vec<ChunkTranscription>> {
+    let ChunkResults {
+        transcribed,
+        failed,
+    } = transcribe_chunks_partial(
+        provider,
+        chunks,
+        options,
+        progress_callback.map(Arc::new),
+        cancel,
+    )
+    .await?;
+
+    if !failed.is_empty() {
+        let total_chunks = transcribed.len() + failed.len();
+        return Err(chunks_error(failed, total_chunks));
+    }
+    Ok(transcribed)
+}
 
-    // Spawn ALL tasks immediately - they'll wait on semaphore inside
-    let mut handles = Vec::with_capacity(total_chunks);
+/// Error for the chunks in `failed` (not empty) out of `total_chunks`. When all
+/// failures share a cause (e.g. a bad API key), the typed error is kept so
+/// frontends can show a hint.
+pub(crate) fn chunks_error(failed: Vec<(usize, WhisError)>, total_chunks: usize) -> WhisError {
+    let mut errors: Vec<WhisError> = failed.into_iter().map(|(_, e)| e).collect();
+    let kind = std::mem::discriminant(&errors[0]);
+    if errors.iter().all(|e| std::mem::discriminant(e) == kind) {
+        return errors.swap_remove(0);
+    }
+    let error_msgs: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    anyhow::anyhow!(
+        "Failed to transcribe {} of {} chunks:\n{}",
+        errors.len(),
+        total_chunks,
+        error_msgs.join("\n")
+    )
+    .into()
+}
 
-    for chunk in chunks {
+/// Like [`transcribe_chunks`], but a failed chunk doesn't fail the others: the chunks
+/// that were transcribed are returned along with the failures. Only errors that
+/// concern all chunks (cancellation, no HTTP client) are returned as `Err`.
+pub(crate) async fn transcribe_chunks_partial(
+    provider: Arc<dyn TranscriptionProvider>,
+    mut chunks: mpsc::UnboundedReceiver<AudioChunk>,
+    options: &TranscriptionOptions,
+    progress_callback: Option<Arc<ProgressCallback>>,
+    cancel: &CancellationToken,
+) -> Result<ChunkResults> {
+    let client = http_client()?;
+
+    // Semaphore to limit concurrent requests
+    let semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_REQUESTS));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let received = Arc::new(AtomicUsize::new(0));
+    let options = Arc::new(options.clone());
+
+    // Spawn a task per chunk as soon as it arrives - they'll wait on semaphore inside
+    let mut handles = Vec::new();
+
+    loop {
+        let chunk = tokio::select! {
+            biased;
+            _ = cancel.cancelled() => break,
+            chunk = chunks.recv() => chunk,
+        };
+        let Some(chunk) = chunk else {
+            break;
+        };
+        received.fetch_add(1, Ordering::SeqCst);
+        let index = chunk.index;
         let semaphore = semaphore.clone();
         let client = client.clone();
-        let api_key = api_key.clone();
+        let provider = provider.clone();
+        let options = options.clone();
         let completed = completed.clone();
+        let received = received.clone();
         let progress_callback = progress_callback.clone();
+        let cancel = cancel.clone();
 
         let handle = tokio::spawn(async move {
             // Acquire permit INSIDE the task - this is the key fix!
             // All tasks spawn immediately, then wait for permits
-            let _permit = semaphore.acquire_owned().await?;
-
-            // Transcribe this chunk (no retry - data is consumed by the request)
-            let result = transcribe_chunk_async(&client, &api_key, chunk).await;
+            let _permit = cancellable(&cancel, async {
+                Ok(semaphore
+                    .acquire_owned()
+                    .await
+                    .context("Request limiter closed")?)
+            })
+            .await?;
+
+            // Transcribe this chunk, retrying transient failures
+            let result = cancellable(
+                &cancel,
+                transcribe_chunk_async(
+                    &client,
+                    provider.as_ref(),
+                    chunk,
+                    &options,
+                    progress_callback.as_deref(),
+                ),
+            )
+            .await;
 
             let transcription = match result {
                 Ok(t) => t,
                 Err(e) => return Err(e),
             };
 
-            let done = completed.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
             if let Some(ref cb) = progress_callback {
-                cb(done, total_chunks);
+                cb(Progress::Chunk {
+                    completed: done,
+                    total: received.load(Ordering::SeqCst),
+                });
             }
             Ok(transcription)
         });
 
-        handles.push(handle);
+        handles.push((index, handle));
     }
 
     // Collect results
-    let mut results = Vec::with_capacity(total_chunks);
-    let mut errors = Vec::new();
+    let mut transcribed = Vec::with_capacity(handles.len());
+    let mut failed = Vec::new();
 
-    for handle in handles {
+    for (index, handle) in handles {
         match handle.await {
-            Ok(Ok(transcription)) => results.push(transcription),
-            Ok(Err(e)) => errors.push(e),
-            Err(e) => errors.push(anyhow::anyhow!("Task panicked: {e}")),
+            Ok(Ok(transcription)) => transcribed.push(transcription),
+            Ok(Err(e)) => failed.push((index, e)),
+            Err(e) => failed.push((index, anyhow::anyhow!("Task panicked: {e}").into())),
         }
     }
 
-    // If any chunks failed, return error with details
-    if !errors.is_empty() {
-        let error_msgs: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
-        anyhow::bail!(
-            "Failed to transcribe {} of {} chunks:\n{}",
-            errors.len(),
-            total_chunks,
-            error_msgs.join("\n")
-        );
+    if cancel.is_cancelled() {
+        return Err(WhisError::Cancelled);
     }
 
     // Sort by index to ensure correct order
-    results.sort_by_key(|r| r.index);
-
-    // Merge transcriptions
-    Ok(merge_transcriptions(results))
+    transcribed.sort_by_key(|r| r.index);
+    Ok(ChunkResults {
+        transcribed,
+        failed,
+    })
 }
 
-/// Merge transcription results, handling overlaps
-fn merge_transcriptions(transcriptions: Vec<ChunkTranscription>) -> String {
+/// Merge transcription results. Chunks are cut at pauses and don't overlap, so their
+/// texts are joined as they are; timestamps are shifted by each chunk's offset so
+/// they follow the recording timeline.
+pub(crate) fn merge_transcriptions(transcriptions: Vec<ChunkTranscription>) -> Transcript {
     if transcriptions.is_empty() {
-        return String::new();
+        return Transcript::default();
     }
 
     if transcriptions.len() == 1 {
-        return transcriptions.into_iter().next().unwrap().text;
+        let chunk = transcriptions.into_iter().next().unwrap();
+        let mut transcript = chunk.transcript;
+        transcript.shift(chunk.offset_secs);
+        if let Some(duration) = transcript.duration.as_mut() {
+            *duration += chunk.offset_secs;
+        }
+        return transcript;
     }
 
     let mut merged = String::new();
+    let mut result = Transcript::default();
 
-    for (i, transcription) in transcriptions.into_iter().enumerate() {
-        let text = transcription.text.trim();
-
-        if i == 0 {
-            // First chunk - use as-is
-            merged.push_str(text);
-        } else if transcription.has_leading_overlap {
-            // This chunk has overlap - try to find and remove duplicate words
-            let cleaned_text = remove_overlap(&merged, text);
-            if !merged.ends_with(' ') && !cleaned_text.is_empty() && !cleaned_text.starts_with(' ')
-            {
-                merged.push(' ');
-            }
-            merged.push_str(&cleaned_text);
-        } else {
-            // No overlap - just append with space
-            if !merged.ends_with(' ') && !text.is_empty() && !text.starts_with(' ') {
-                merged.push(' ');
-            }
-            merged.push_str(text);
+    for transcription in transcriptions {
+        let mut transcript = transcription.transcript;
+        transcript.shift(transcription.offset_secs);
+        let text = std::mem::take(&mut transcript.text);
+        merge_timestamps(&mut result, transcript, transcription.offset_secs);
+
+        let text = text.trim();
+        if !merged.is_empty() && !text.is_empty() {
+            merged.push(' ');
         }
+        merged.push_str(text);
     }
 
-    merged
+    result.text = merged;
+    result
 }
 
-/// Remove overlapping text from the beginning of new_text that matches end of existing_text
-fn remove_overlap(existing: &str, new_text: &str) -> String {
-    let existing_words: Vec<&str> = existing.split_whitespace().collect();
-    let new_words: Vec<&str> = new_text.split_whitespace().collect();
-
-    if existing_words.is_empty() || new_words.is_empty() {
-        return new_text.to_string();
+/// Append a chunk's segments and words (already shifted onto the recording timeline)
+fn merge_timestamps(result: &mut Transcript, chunk: Transcript, offset_secs: f64) {
+    if result.language.is_none() {
+        result.language = chunk.language;
     }
+    if let Some(duration) = chunk.duration {
+        result.duration = Some(result.duration.unwrap_or(0.0).max(offset_secs + duration));
+    }
+    result.segments.extend(chunk.segments);
+    result.words.extend(chunk.words);
+}
 
-    // Look for overlap in the last N words of existing and first N words of new
-    // ~2 seconds of audio overlap = roughly 5-15 words
-    let search_end = existing_words.len().min(MAX_OVERLAP_WORDS);
-    let search_new = new_words.len().min(MAX_OVERLAP_WORDS);
-
-    // Find the longest matching overlap
-    let mut best_overlap = 0;
-
-    for overlap_len in 1..=search_end.min(search_new) {
-        let end_slice = &existing_words[existing_words.len() - overlap_len..];
-        let start_slice = &new_words[..overlap_len];
-
-        // Case-insensitive comparison
-        let matches = end_slice
-            .iter()
-            .zip(start_slice.iter())
-            .all(|(a, b)| a.eq_ignore_ascii_case(b));
-
-        if matches {
-            best_overlap = overlap_len;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::{Segment, Word};
+
+    fn chunk(index: usize, offset_secs: f64, text: &str, duration: f64) -> ChunkTranscription {
+        ChunkTranscription {
+            index,
+            transcript: Transcript {
+                text: text.to_string(),
+                language: Some("en".to_string()),
+                duration: Some(duration),
+                segments: vec![Segment {
+                    start: 1.0,
+                    end: 2.5,
+                    text: text.to_string(),
+                }],
+                words: vec![Word {
+                    start: 1.0,
+                    end: 1.5,
+                    word: text.to_string(),
+                }],
+            },
+            offset_secs,
         }
     }
 
-    if best_overlap > 0 {
-        // Skip the overlapping words
-        new_words[best_overlap..].join(" ")
-    } else {
-        new_text.to_string()
+    #[test]
+    fn test_merge_shifts_chunks_onto_recording_timeline() {
+        let merged = merge_transcriptions(vec![
+            chunk(0, 0.0, " first ", 300.0),
+            chunk(1, 300.0, "second", 290.0),
+            chunk(2, 590.0, "", 10.0),
+        ]);
+        assert_eq!(merged.text, "first second");
+        assert_eq!(merged.language.as_deref(), Some("en"));
+        assert_eq!(merged.duration, Some(600.0));
+        let starts: Vec<f64> = merged.segments.iter().map(|s| s.start).collect();
+        assert_eq!(starts, [1.0, 301.0, 591.0]);
+        assert_eq!(merged.segments[1].end, 302.5);
+        assert_eq!(merged.words[2].start, 591.0);
+    }
+
+    #[test]
+    fn test_merge_single_chunk_keeps_its_offset() {
+        let merged = merge_transcriptions(vec![chunk(0, 12.0, "only", 30.0)]);
+        assert_eq!(merged.text, "only");
+        assert_eq!(merged.segments[0].start, 13.0);
+        assert_eq!(merged.duration, Some(42.0));
+        assert!(merge_transcriptions(Vec::new()).text.is_empty());
     }
 }
@@ -0,0 +1,246 @@
+//! Structured transcripts with timestamps, and writers for caption formats
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::{Result, WhisError};
+
+/// A transcribed span of speech
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Segment {
+    /// Start time in seconds
+    pub start: f64,
+    /// End time in seconds
+    pub end: f64,
+    pub text: String,
+}
+
+/// A single transcribed word
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Word {
+    /// Start time in seconds
+    pub start: f64,
+    /// End time in seconds
+    pub end: f64,
+    pub word: String,
+}
+
+/// Transcription result. `segments` and `words` are empty when the
+/// provider or model doesn't report timestamps.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Transcript {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Audio duration in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+    #[serde(default)]
+    pub segments: Vec<Segment>,
+    #[serde(default)]
+    pub words: Vec<Word>,
+}
+
+impl Transcript {
+    /// Transcript without timestamps
+    pub fn from_text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Move all timestamps by `offset` seconds (used to place a chunk on the recording timeline)
+    pub fn shift(&mut self, offset: f64) {
+        for segment in &mut self.segments {
+            segment.start += offset;
+            segment.end += offset;
+        }
+        for word in &mut self.words {
+            word.start += offset;
+            word.end += offset;
+        }
+    }
+
+    /// SubRip subtitles
+    pub fn to_srt(&self) -> String {
+        let mut out = String::new();
+        for (i, segment) in self.caption_segments().iter().enumerate() {
+            out.push_str(&format!(
+                "{}\n{} --> {}\n{}\n\n",
+                i + 1,
+                format_timestamp(segment.start, ','),
+                format_timestamp(segment.end, ','),
+                segment.text.trim()
+            ));
+        }
+        out
+    }
+
+    /// WebVTT subtitles
+    pub fn to_vtt(&self) -> String {
+        let mut out = String::from("WEBVTT\n\n");
+        for segment in self.caption_segments() {
+            out.push_str(&format!(
+                "{} --> {}\n{}\n\n",
+                format_timestamp(segment.start, '.'),
+                format_timestamp(segment.end, '.'),
+                segment.text.trim()
+            ));
+        }
+        out
+    }
+
+    /// Pretty-printed JSON with segments and words
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self)
+            .map_err(|e| WhisError::Output(format!("failed to serialize as JSON: {e}")))
+    }
+
+    /// Render in the given format
+    pub fn render(&self, format: OutputFormat) -> Result<String> {
+        Ok(match format {
+            OutputFormat::Text => format!("{}\n", self.text.trim()),
+            OutputFormat::Srt => self.to_srt(),
+            OutputFormat::Vtt => self.to_vtt(),
+            OutputFormat::Json => self.to_json()?,
+        })
+    }
+
+    /// Write to `path`, picking the format from its extension
+    pub fn write_to(&self, path: &Path) -> Result<()> {
+        let content = self.render(OutputFormat::from_path(path))?;
+        std::fs::write(path, content)
+            .map_err(|e| WhisError::Output(format!("{}: {e}", path.display())))
+    }
+
+    /// Segments for captions. Without timestamps the whole text becomes one cue.
+    fn caption_segments(&self) -> Vec<Segment> {
+        if !self.segments.is_empty() || self.text.trim().is_empty() {
+            return self.segments.clone();
+        }
+        vec![Segment {
+            start: 0.0,
+            end: self.duration.unwrap_or(0.0),
+            text: self.text.clone(),
+        }]
+    }
+}
+
+/// Output file formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Srt,
+    Vtt,
+    Json,
+}
+
+impl OutputFormat {
+    /// Pick the format from a file extension, defaulting to plain text
+    pub fn from_path(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("srt") => Self::Srt,
+            Some("vtt") => Self::Vtt,
+            Some("json") => Self::Json,
+            _ => Self::Text,
+        }
+    }
+
+    /// File extension for this format
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Text => "txt",
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+            Self::Json => "json",
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        [Self::Text, Self::Srt, Self::Vtt, Self::Json]
+            .into_iter()
+            .find(|format| format.extension().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown format '{s}' (expected txt, srt, vtt or json)"))
+    }
+}
+
+/// Format seconds as `HH:MM:SS,mmm` (SRT) or `HH:MM:SS.mmm` (WebVTT)
+fn format_timestamp(seconds: f64, separator: char) -> String {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    let hours = total_ms / 3_600_000;
+    let minutes = (total_ms / 60_000) % 60;
+    let secs = (total_ms / 1000) % 60;
+    let ms = total_ms % 1000;
+    format!("{hours:02}:{minutes:02}:{secs:02}{separator}{ms:03}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timestamp_rounds_to_milliseconds() {
+        assert_eq!(format_timestamp(0.0, ','), "00:00:00,000");
+        assert_eq!(format_timestamp(61.2345, ','), "00:01:01,235");
+        assert_eq!(format_timestamp(-1.0, ','), "00:00:00,000");
+        assert_eq!(format_timestamp(4000.5, '.'), "01:06:40.500");
+    }
+
+    #[test]
+    fn test_timestamp_rounds_across_hour_boundary() {
+        assert_eq!(format_timestamp(3599.9994, ','), "00:59:59,999");
+        assert_eq!(format_timestamp(3599.9996, ','), "01:00:00,000");
+        assert_eq!(format_timestamp(7199.9999, '.'), "02:00:00.000");
+    }
+
+    #[test]
+    fn test_srt_and_vtt_cues() {
+        let transcript = Transcript {
+            text: "Hello there. General Kenobi.".to_string(),
+            segments: vec![
+                Segment {
+                    start: 0.0,
+                    end: 1.5,
+                    text: " Hello there.".to_string(),
+                },
+                Segment {
+                    start: 3599.5,
+                    end: 3601.25,
+                    text: " General Kenobi.".to_string(),
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            transcript.to_srt(),
+            "1\n00:00:00,000 --> 00:00:01,500\nHello there.\n\n\
+             2\n00:59:59,500 --> 01:00:01,250\nGeneral Kenobi.\n\n"
+        );
+        assert!(
+            transcript
+                .to_vtt()
+                .starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:01.500\nHello there.\n\n")
+        );
+    }
+
+    #[test]
+    fn test_captions_without_timestamps_use_one_cue() {
+        let mut transcript = Transcript::from_text("Just text");
+        transcript.duration = Some(2.0);
+        assert_eq!(
+            transcript.to_srt(),
+            "1\n00:00:00,000 --> 00:00:02,000\nJust text\n\n"
+        );
+    }
+}
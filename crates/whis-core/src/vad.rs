@@ -0,0 +1,377 @@
+//! Voice activity detection
+//!
+//! An energy-based detector that finds silent stretches in a recording so they
+//! can be cut before upload: long pauses cost money and make Whisper invent text.
+//! Leading and trailing silence is stripped and long pauses are shortened, keeping
+//! a little padding around speech. The returned [`TimeMap`] puts transcript
+//! timestamps back on the original recording timeline.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use crate::transcript::Transcript;
+
+/// Length of an analysis frame
+const FRAME_MS: usize = 30;
+/// Frames below this level are never treated as the noise floor
+const MIN_NOISE_FLOOR_DB: f32 = -70.0;
+/// Speech is expected within this range of the loudest frames
+const SPEECH_RANGE_DB: f32 = 20.0;
+
+/// How eagerly silence is cut before transcription
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VadAggressiveness {
+    /// Keep the recording as is
+    #[default]
+    Off,
+    /// Only cut clear silence: pauses over a second, generous padding
+    Low,
+    Medium,
+    /// Also cut short pauses and quiet background noise
+    High,
+}
+
+/// Detector tuning for one aggressiveness level
+struct VadParams {
+    /// Level above the noise floor that counts as speech
+    margin_db: f32,
+    /// Shortest pause that gets shortened
+    min_silence_ms: usize,
+    /// Silence kept next to speech
+    padding_ms: usize,
+}
+
+impl VadAggressiveness {
+    pub const ALL: [Self; 4] = [Self::Off, Self::Low, Self::Medium, Self::High];
+
+    fn params(self) -> Option<VadParams> {
+        let (margin_db, min_silence_ms, padding_ms) = match self {
+            Self::Off => return None,
+            Self::Low => (6.0, 1000, 300),
+            Self::Medium => (9.0, 600, 200),
+            Self::High => (12.0, 300, 100),
+        };
+        Some(VadParams {
+            margin_db,
+            min_silence_ms,
+            padding_ms,
+        })
+    }
+}
+
+impl fmt::Display for VadAggressiveness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Off => "off",
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for VadAggressiveness {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|level| level.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown VAD level '{s}' (expected off, low, medium or high)"))
+    }
+}
+
+/// A stretch of the original recording that was kept
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Span {
+    /// Start in the trimmed audio, in seconds
+    trimmed_start: f64,
+    /// Start in the original recording, in seconds
+    original_start: f64,
+}
+
+/// Maps times in trimmed audio back to the original recording
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TimeMap {
+    /// Kept spans in order; empty when nothing was cut
+    spans: Vec<Span>,
+    /// Length of the original recording in seconds
+    original_duration: f64,
+}
+
+impl TimeMap {
+    /// Whether trimming left the recording unchanged
+    pub fn is_identity(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Position in the original recording of `secs` in the trimmed audio.
+    /// A time exactly at a cut is placed after the removed silence.
+    pub fn to_original(&self, secs: f64) -> f64 {
+        self.map(secs, |span| span.trimmed_start <= secs)
+    }
+
+    /// Like [`to_original`](Self::to_original), but a time exactly at a cut is placed
+    /// before the removed silence, so a segment ending there doesn't stretch over it
+    fn end_to_original(&self, secs: f64) -> f64 {
+        self.map(secs, |span| span.trimmed_start < secs)
+    }
+
+    /// Map `secs` through the last span for which `starts_before` holds
+    fn map(&self, secs: f64, starts_before: impl Fn(&Span) -> bool) -> f64 {
+        let index = self.spans.partition_point(starts_before).saturating_sub(1);
+        match self.spans.get(index) {
+            Some(span) => span.original_start + (secs - span.trimmed_start),
+            None => secs,
+        }
+    }
+
+    /// Move a transcript of the trimmed audio onto the original timeline
+    pub fn restore(&self, transcript: &mut Transcript) {
+        if self.is_identity() {
+            return;
+        }
+        for segment in &mut transcript.segments {
+            segment.start = self.to_original(segment.start);
+            segment.end = self.end_to_original(segment.end).max(segment.start);
+        }
+        for word in &mut transcript.words {
+            word.start = self.to_original(word.start);
+            word.end = self.end_to_original(word.end).max(word.start);
+        }
+        if transcript.duration.is_some() {
+            transcript.duration = Some(self.original_duration);
+        }
+    }
+}
+
+/// Cut silence from interleaved samples, returning the kept audio and its time map
+pub fn trim_silence(
+    samples: Vec<f32>,
+    sample_rate: u32,
+    channels: u16,
+    aggressiveness: VadAggressiveness,
+) -> (Vec<f32>, TimeMap) {
+    let channels = channels.max(1) as usize;
+    let Some(params) = aggressiveness.params() else {
+        return (samples, TimeMap::default());
+    };
+    let frame_len = (sample_rate as usize * FRAME_MS / 1000).max(1);
+    let cuts = silent_frames(&samples, frame_len, channels, &params);
+    if cuts.is_empty() {
+        return (samples, TimeMap::default());
+    }
+
+    // Keep everything between the cuts (in sample frames)
+    let total_frames = samples.len() / channels;
+    let mut kept = Vec::with_capacity(cuts.len() + 1);
+    let mut position = 0;
+    for cut in cuts {
+        let cut_start = (cut.start * frame_len).min(total_frames);
+        if cut_start > position {
+            kept.push(position..cut_start);
+        }
+        position = (cut.end * frame_len).min(total_frames);
+    }
+    if position < total_frames {
+        kept.push(position..total_frames);
+    }
+
+    let rate = sample_rate as f64;
+    let mut trimmed = Vec::with_capacity(kept.iter().map(|r| r.len() * channels).sum());
+    let mut spans = Vec::with_capacity(kept.len());
+    for range in kept {
+        spans.push(Span {
+            trimmed_start: (trimmed.len() / channels) as f64 / rate,
+            original_start: range.start as f64 / rate,
+        });
+        trimmed.extend_from_slice(&samples[range.start * channels..range.end * channels]);
+    }
+
+    let map = TimeMap {
+        spans,
+        original_duration: total_frames as f64 / rate,
+    };
+    (trimmed, map)
+}
+
+/// Analysis frames to cut: silence beyond the padding around speech
+fn silent_frames(
+    samples: &[f32],
+    frame_len: usize,
+    channels: usize,
+    params: &VadParams,
+) -> Vec<Range<usize>> {
+    let levels: Vec<f32> = samples.chunks(frame_len * channels).map(level_db).collect();
+    if levels.len() < 2 {
+        return Vec::new();
+    }
+
+    // Compare each frame against the room's noise floor, but never demand more
+    // than the loud frames reach so recordings without pauses keep their speech
+    let mut sorted = levels.clone();
+    sorted.sort_by(f32::total_cmp);
+    let percentile = |p: usize| sorted[(sorted.len() - 1) * p / 100];
+    let noise_floor = percentile(10).max(MIN_NOISE_FLOOR_DB);
+    let threshold = (noise_floor + params.margin_db).min(percentile(95) - SPEECH_RANGE_DB);
+    let speech: Vec<bool> = levels.iter().map(|&level| level > threshold).collect();
+    if !speech.contains(&true) {
+        return Vec::new();
+    }
+
+    let min_silence = (params.min_silence_ms / FRAME_MS).max(1);
+    let padding = params.padding_ms / FRAME_MS;
+
+    let mut cuts = Vec::new();
+    let mut start = 0;
+    while start < speech.len() {
+        if speech[start] {
+            start += 1;
+            continue;
+        }
+        let end = speech[start..]
+            .iter()
+            .position(|&s| s)
+            .map_or(speech.len(), |offset| start + offset);
+        let len = end - start;
+        if start == 0 {
+            // Leading silence: keep only the padding before speech
+            if len > padding {
+                cuts.push(0..end - padding);
+            }
+        } else if end == speech.len() {
+            // Trailing silence: keep only the padding after speech
+            if len > padding {
+                cuts.push(start + padding..end);
+            }
+        } else if len >= min_silence && len > 2 * padding {
+            cuts.push(start + padding..end - padding);
+        }
+        start = end;
+    }
+    cuts
+}
+
+/// RMS level of a frame in dBFS
+fn level_db(frame: &[f32]) -> f32 {
+    let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len().max(1) as f32;
+    10.0 * mean_square.max(1e-10).log10()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transcript::Segment;
+
+    /// 1 kHz, so an analysis frame is 30 samples
+    const RATE: u32 = 1000;
+    const FRAME: usize = RATE as usize * FRAME_MS / 1000;
+
+    /// Mono audio from (is speech, length in frames) runs
+    fn audio(runs: &[(bool, usize)]) -> Vec<f32> {
+        runs.iter()
+            .flat_map(|&(speech, frames)| {
+                std::iter::repeat_n(if speech { 0.5 } else { 0.0 }, frames * FRAME)
+            })
+            .collect()
+    }
+
+    /// Kept spans as (trimmed start, original start) in seconds
+    fn time_map(spans: &[(f64, f64)], original_duration: f64) -> TimeMap {
+        TimeMap {
+            spans: spans
+                .iter()
+                .map(|&(trimmed_start, original_start)| Span {
+                    trimmed_start,
+                    original_start,
+                })
+                .collect(),
+            original_duration,
+        }
+    }
+
+    #[test]
+    fn test_default_keeps_recording() {
+        assert_eq!(VadAggressiveness::default(), VadAggressiveness::Off);
+        let samples = audio(&[(false, 50), (true, 50), (false, 50)]);
+        let (trimmed, map) = trim_silence(samples.clone(), RATE, 1, VadAggressiveness::default());
+        assert_eq!(trimmed, samples);
+        assert!(map.is_identity());
+    }
+
+    #[test]
+    fn test_silent_frames_cut_beyond_padding() {
+        let params = VadAggressiveness::Low.params().unwrap();
+        let samples = audio(&[
+            (false, 50),
+            (true, 50),
+            (false, 50),
+            (true, 50),
+            // Shorter than a second: kept
+            (false, 20),
+            (true, 30),
+            (false, 50),
+        ]);
+        assert_eq!(
+            silent_frames(&samples, FRAME, 1, &params),
+            vec![0..40, 110..140, 260..300]
+        );
+    }
+
+    #[test]
+    fn test_silent_frames_without_speech_cuts_nothing() {
+        let params = VadAggressiveness::High.params().unwrap();
+        let samples = audio(&[(false, 100)]);
+        assert!(silent_frames(&samples, FRAME, 1, &params).is_empty());
+    }
+
+    #[test]
+    fn test_trim_silence_maps_back_to_original() {
+        let samples = audio(&[(false, 50), (true, 50), (false, 50), (true, 50)]);
+        let (trimmed, map) = trim_silence(samples, RATE, 1, VadAggressiveness::Low);
+        // 40 leading and 30 middle frames cut
+        assert_eq!(trimmed.len(), 130 * FRAME);
+        assert_eq!(map, time_map(&[(0.0, 1.2), (2.1, 4.2)], 6.0));
+    }
+
+    #[test]
+    fn test_to_original() {
+        let map = time_map(&[(0.0, 1.0), (2.0, 5.0)], 8.0);
+        assert_eq!(map.to_original(0.0), 1.0);
+        assert_eq!(map.to_original(1.5), 2.5);
+        // Exactly at the cut: after the removed silence
+        assert_eq!(map.to_original(2.0), 5.0);
+        assert_eq!(map.to_original(2.5), 5.5);
+        assert_eq!(TimeMap::default().to_original(3.0), 3.0);
+    }
+
+    #[test]
+    fn test_restore_segment_ending_at_cut() {
+        let map = time_map(&[(0.0, 1.0), (2.0, 5.0)], 8.0);
+        let segment = |start, end| Segment {
+            start,
+            end,
+            text: String::new(),
+        };
+        let mut transcript = Transcript {
+            text: String::new(),
+            duration: Some(3.0),
+            segments: vec![segment(0.5, 2.0), segment(2.0, 2.5), segment(2.0, 2.0)],
+            ..Default::default()
+        };
+        map.restore(&mut transcript);
+
+        let times: Vec<_> = transcript
+            .segments
+            .iter()
+            .map(|s| (s.start, s.end))
+            .collect();
+        // The first segment ends before the silence instead of stretching over it
+        assert_eq!(times, vec![(1.5, 3.0), (5.0, 5.5), (5.0, 5.0)]);
+        assert_eq!(transcript.duration, Some(8.0));
+    }
+}
@@ -0,0 +1,194 @@
+//! Realtime streaming against a local mock WebSocket server
+
+// The handshake callback signature is dictated by tungstenite
+#![allow(clippy::result_large_err)]
+
+use base64::Engine;
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use whis_core::{RealtimeConfig, RealtimeEvent, WhisError, stream_transcription};
+
+/// What the mock server saw during a session
+#[derive(Debug, Default)]
+struct Session {
+    authorization: Option<String>,
+    session_update: Option<Value>,
+    /// Decoded PCM16 bytes across all append messages
+    audio_bytes: usize,
+}
+
+/// Serve one connection. The first append yields a finished segment; the final
+/// commit yields a second one, unless `commit_empty` is set.
+async fn mock_server(commit_empty: bool) -> (String, oneshot::Receiver<Session>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!(
+        "ws://{}/v1/realtime?intent=transcription",
+        listener.local_addr().unwrap()
+    );
+    let (done_tx, done_rx) = oneshot::channel();
+
+    tokio::spawn(async move {
+        let (tcp, _) = listener.accept().await.unwrap();
+        let mut session = Session::default();
+        let mut authorization = None;
+        let mut ws = tokio_tungstenite::accept_hdr_async(tcp, |req: &Request, resp: Response| {
+            authorization = req
+                .headers()
+                .get("Authorization")
+                .map(|v| v.to_str().unwrap().to_string());
+            Ok(resp)
+        })
+        .await
+        .unwrap();
+        session.authorization = authorization;
+
+        let mut first_append = true;
+        while let Some(Ok(message)) = ws.next().await {
+            let Message::Text(text) = message else {
+                continue;
+            };
+            let event: Value = serde_json::from_str(&text).unwrap();
+            let replies = match event["type"].as_str().unwrap() {
+                "transcription_session.update" => {
+                    session.session_update = Some(event);
+                    vec![]
+                }
+                "input_audio_buffer.append" => {
+                    let audio = base64::engine::general_purpose::STANDARD
+                        .decode(event["audio"].as_str().unwrap())
+                        .unwrap();
+                    session.audio_bytes += audio.len();
+                    if !std::mem::take(&mut first_append) {
+                        continue;
+                    }
+                    vec![
+                        json!({ "type": "input_audio_buffer.speech_stopped", "item_id": "item_1" }),
+                        json!({ "type": "input_audio_buffer.committed", "item_id": "item_1" }),
+                        json!({ "type": "conversation.item.input_audio_transcription.delta", "item_id": "item_1", "delta": "Hello" }),
+                        json!({ "type": "conversation.item.input_audio_transcription.delta", "item_id": "item_1", "delta": " world" }),
+                        json!({ "type": "conversation.item.input_audio_transcription.completed", "item_id": "item_1", "transcript": "Hello world." }),
+                    ]
+                }
+                "input_audio_buffer.commit" if commit_empty => vec![json!({
+                    "type": "error",
+                    "error": { "code": "input_audio_buffer_commit_empty", "message": "buffer too small" }
+                })],
+                "input_audio_buffer.commit" => vec![
+                    json!({ "type": "input_audio_buffer.committed", "item_id": "item_2" }),
+                    json!({ "type": "conversation.item.input_audio_transcription.completed", "item_id": "item_2", "transcript": "Bye." }),
+                ],
+                other => panic!("unexpected client event {other}"),
+            };
+            for reply in replies {
+                ws.send(Message::text(reply.to_string())).await.unwrap();
+            }
+        }
+        let _ = done_tx.send(session);
+    });
+
+    (url, done_rx)
+}
+
+fn config(url: String) -> RealtimeConfig {
+    RealtimeConfig {
+        url,
+        api_key: Some("test-key".to_string()),
+        model: "gpt-4o-transcribe".to_string(),
+        language: Some("en".to_string()),
+        prompt: None,
+    }
+}
+
+/// Send `seconds` of 48 kHz stereo silence in 10 ms buffers, then close the channel
+fn recording(seconds: f32) -> mpsc::UnboundedReceiver<Vec<f32>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    for _ in 0..(seconds * 100.0) as usize {
+        tx.send(vec![0.0; 960]).unwrap();
+    }
+    rx
+}
+
+#[tokio::test]
+async fn test_streams_audio_and_collects_transcripts() {
+    let (url, session) = mock_server(false).await;
+    let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+
+    let transcript = stream_transcription(&config(url), recording(1.0), 48000, 2, events_tx)
+        .await
+        .unwrap();
+    assert_eq!(transcript.text, "Hello world. Bye.");
+
+    let mut events = Vec::new();
+    while let Some(event) = events_rx.recv().await {
+        events.push(event);
+    }
+    assert_eq!(
+        events,
+        vec![
+            RealtimeEvent::Partial {
+                item_id: "item_1".into(),
+                text: "Hello".into()
+            },
+            RealtimeEvent::Partial {
+                item_id: "item_1".into(),
+                text: "Hello world".into()
+            },
+            RealtimeEvent::Final {
+                item_id: "item_1".into(),
+                text: "Hello world.".into()
+            },
+            RealtimeEvent::Final {
+                item_id: "item_2".into(),
+                text: "Bye.".into()
+            },
+        ]
+    );
+
+    let session = session.await.unwrap();
+    assert_eq!(session.authorization.as_deref(), Some("Bearer test-key"));
+    let update = session.session_update.unwrap();
+    assert_eq!(update["session"]["input_audio_format"], "pcm16");
+    assert_eq!(
+        update["session"]["input_audio_transcription"]["language"],
+        "en"
+    );
+    // One second of audio, downmixed and resampled to 24 kHz PCM16
+    assert_eq!(session.audio_bytes, 24000 * 2);
+}
+
+#[tokio::test]
+async fn test_empty_final_commit_is_not_an_error() {
+    let (url, _session) = mock_server(true).await;
+    let (events_tx, _events_rx) = mpsc::unbounded_channel();
+
+    let transcript = stream_transcription(&config(url), recording(0.5), 48000, 2, events_tx)
+        .await
+        .unwrap();
+    assert_eq!(transcript.text, "Hello world.");
+}
+
+#[tokio::test]
+async fn test_rejected_handshake_is_an_authentication_error() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/v1/realtime", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let (tcp, _) = listener.accept().await.unwrap();
+        let _ = tokio_tungstenite::accept_hdr_async(tcp, |_: &Request, _: Response| {
+            Err(tokio_tungstenite::tungstenite::http::Response::builder()
+                .status(401)
+                .body(Some("invalid api key".to_string()))
+                .unwrap())
+        })
+        .await;
+    });
+
+    let (events_tx, _events_rx) = mpsc::unbounded_channel();
+    let err = stream_transcription(&config(url), recording(0.1), 48000, 2, events_tx)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, WhisError::Authentication { .. }), "{err:?}");
+}
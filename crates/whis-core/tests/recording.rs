@@ -0,0 +1,83 @@
+//! Recording from a test source and transcribing while recording, against a mock provider
+
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use whis_core::{
+    AudioRecorder, CancellationToken, EncodingOptions, Result, ToneSource, Transcript,
+    TranscriptionOptions, TranscriptionProvider, TranscriptionRequest, decode_bytes,
+    transcribe_while_recording,
+};
+
+/// Answers every request with a fixed transcript and keeps the uploads
+#[derive(Default)]
+struct MockProvider {
+    uploads: Mutex<Vec<TranscriptionRequest>>,
+}
+
+#[async_trait]
+impl TranscriptionProvider for MockProvider {
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+
+    async fn transcribe(
+        &self,
+        _client: &reqwest::Client,
+        request: TranscriptionRequest,
+    ) -> Result<Transcript> {
+        self.uploads.lock().unwrap().push(request);
+        Ok(Transcript {
+            text: "a steady tone".to_string(),
+            ..Default::default()
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_transcribes_a_tone_while_recording() {
+    let provider = Arc::new(MockProvider::default());
+    let mut recorder = AudioRecorder::new().unwrap();
+    recorder.set_source(Box::new(ToneSource::new(440.0)));
+    let mut samples = recorder.stream_samples();
+    recorder.start_recording().unwrap();
+    let (sample_rate, channels) = (recorder.sample_rate(), recorder.channels());
+
+    // Pass the recorded audio on to the transcriber, counting it on the way
+    let (audio_tx, audio_rx) = tokio::sync::mpsc::unbounded_channel();
+    let transcription = tokio::spawn({
+        let provider = provider.clone();
+        async move {
+            transcribe_while_recording(
+                provider,
+                audio_rx,
+                sample_rate,
+                channels,
+                &TranscriptionOptions::default(),
+                EncodingOptions::default(),
+                None,
+                &CancellationToken::new(),
+            )
+            .await
+        }
+    });
+    let wanted = sample_rate as usize * channels as usize / 2;
+    let mut recorded = 0;
+    while recorded < wanted {
+        let buffer = samples.recv().await.expect("recording ended early");
+        recorded += buffer.len();
+        audio_tx.send(buffer).unwrap();
+    }
+    recorder.stop_stream();
+    while let Some(buffer) = samples.recv().await {
+        audio_tx.send(buffer).unwrap();
+    }
+    drop(audio_tx);
+
+    let transcript = transcription.await.unwrap().unwrap();
+    assert_eq!(transcript.text, "a steady tone");
+
+    let uploads = provider.uploads.lock().unwrap();
+    assert_eq!(uploads.len(), 1);
+    assert!(!uploads[0].audio_data.is_empty());
+    assert!(decode_bytes(uploads[0].audio_data.clone()).is_ok());
+}
@@ -2,12 +2,14 @@ use crate::settings::Settings;
 use crate::shortcuts::ShortcutBackendInfo;
 use crate::state::{AppState, RecordingState};
 use tauri::{AppHandle, State};
-use whis_core::ApiConfig;
+use whis_core::{ApiConfig, InputDevice, MAX_PRE_ROLL_MS};
 
 #[derive(serde::Serialize)]
 pub struct StatusResponse {
     pub state: String,
     pub config_valid: bool,
+    /// Microphone kept open between recordings for the warm mic
+    pub warm_mic: bool,
 }
 
 #[derive(serde::Serialize)]
@@ -25,17 +27,20 @@ pub async fn get_status(state: State<'_, AppState>) -> Result<StatusResponse, St
     let current_state = *state.state.lock().unwrap();
 
     // Check if API key is configured (either in settings or already loaded)
-    let config_valid = state.api_config.lock().unwrap().is_some()
-        || state.settings.lock().unwrap().openai_api_key.is_some()
-        || std::env::var("OPENAI_API_KEY").is_ok();
+    let config_valid = state.provider.lock().unwrap().is_some() || {
+        let settings = state.settings.lock().unwrap();
+        settings.local_model.is_some() || ApiConfig::from_settings(&settings).is_ok()
+    };
 
     Ok(StatusResponse {
         state: match current_state {
             RecordingState::Idle => "Idle".to_string(),
             RecordingState::Recording => "Recording".to_string(),
+            RecordingState::Paused => "Paused".to_string(),
             RecordingState::Transcribing => "Transcribing".to_string(),
         },
         config_valid,
+        warm_mic: crate::tray::is_warm(&state),
     })
 }
 
@@ -45,6 +50,37 @@ pub async fn toggle_recording(app: AppHandle) -> Result<(), String> {
     Ok(())
 }
 
+#[tauri::command]
+pub async fn cancel_transcription(app: AppHandle) -> Result<(), String> {
+    crate::tray::cancel_transcription(app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn discard_recording(app: AppHandle) -> Result<(), String> {
+    crate::tray::discard_recording(app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn toggle_pause(app: AppHandle) -> Result<(), String> {
+    crate::tray::toggle_pause(app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_input_devices(app: AppHandle) -> Result<Vec<InputDevice>, String> {
+    let devices = whis_core::list_input_devices().map_err(|e| e.to_string())?;
+    // Keep the tray submenu in sync with what the window shows
+    crate::tray::update_device_menu(&app, &devices);
+    Ok(devices)
+}
+
+#[tauri::command]
+pub async fn set_input_device(app: AppHandle, name: Option<String>) -> Result<(), String> {
+    crate::tray::select_input_device(&app, name)
+}
+
 #[tauri::command]
 pub async fn get_settings(state: State<'_, AppState>) -> Result<Settings, String> {
     let mut settings = state.settings.lock().unwrap();
@@ -95,14 +131,41 @@ pub async fn save_settings(
     state: State<'_, AppState>,
     settings: Settings,
 ) -> Result<SaveSettingsResponse, String> {
+    if let Some(temperature) = settings.temperature
+        && !(0.0..=1.0).contains(&temperature)
+    {
+        return Err("Temperature must be between 0 and 1".to_string());
+    }
+    if settings.pre_roll_ms.is_some_and(|ms| ms > MAX_PRE_ROLL_MS) {
+        return Err(format!("Pre-roll can be at most {MAX_PRE_ROLL_MS} ms"));
+    }
+
     // Check what changed
-    let (api_key_changed, shortcut_changed) = {
+    let (provider_changed, shortcut_changed, warm_mic_changed, old_secondary) = {
         let current = state.settings.lock().unwrap();
+        let old_secondary = [
+            current.cancel_shortcut.clone(),
+            current.discard_shortcut.clone(),
+            current.pause_shortcut.clone(),
+        ];
         (
-            current.openai_api_key != settings.openai_api_key,
+            current.openai_api_key != settings.openai_api_key
+                || current.base_url != settings.base_url
+                || current.model != settings.model
+                || current.local_model != settings.local_model
+                || current.models_dir != settings.models_dir,
             current.shortcut != settings.shortcut,
+            current.pre_roll_ms != settings.pre_roll_ms
+                || current.input_device != settings.input_device,
+            old_secondary,
         )
     };
+    let secondary_changed = old_secondary
+        != [
+            settings.cancel_shortcut.clone(),
+            settings.discard_shortcut.clone(),
+            settings.pause_shortcut.clone(),
+        ];
 
     {
         let mut state_settings = state.settings.lock().unwrap();
@@ -110,15 +173,24 @@ pub async fn save_settings(
         state_settings.save().map_err(|e| e.to_string())?;
     }
 
-    // Clear cached API config if API key changed
-    if api_key_changed {
-        *state.api_config.lock().unwrap() = None;
+    // Clear cached provider if key, endpoint or model changed
+    if provider_changed {
+        *state.provider.lock().unwrap() = None;
+    }
+
+    // Open, reopen or release the warm microphone
+    if warm_mic_changed {
+        crate::tray::apply_warm_mic(&app);
     }
 
-    // Only update shortcut if it actually changed
+    // Only update shortcuts if they actually changed. update_shortcut also
+    // re-registers the cancel, discard and pause shortcuts.
     let needs_restart = if shortcut_changed {
         crate::shortcuts::update_shortcut(&app, &settings.shortcut)
             .map_err(|e| e.to_string())?
+    } else if secondary_changed {
+        crate::shortcuts::update_secondary_shortcuts(&app, &old_secondary)
+            .map_err(|e| e.to_string())?
     } else {
         false
     };
@@ -127,12 +199,17 @@ pub async fn save_settings(
 }
 
 #[tauri::command]
-pub fn validate_api_key(api_key: String) -> Result<bool, String> {
+pub fn validate_api_key(api_key: String, base_url: Option<String>) -> Result<bool, String> {
     // Validate format: OpenAI keys start with "sk-"
     if api_key.is_empty() {
         return Ok(true); // Empty is valid (will fall back to env var)
     }
 
+    // Self-hosted servers accept arbitrary keys
+    if base_url.is_some_and(|url| !url.is_empty()) {
+        return Ok(true);
+    }
+
     if !api_key.starts_with("sk-") {
         return Err("Invalid key format. OpenAI keys start with 'sk-'".to_string());
     }
@@ -14,6 +14,9 @@ pub fn run() {
             // Load settings from disk
             let loaded_settings = settings::Settings::load();
 
+            // Initialize state first so the tray can keep its menu items in it
+            app.manage(state::AppState::new(loaded_settings, false));
+
             // Initialize system tray (optional - may fail on tray-less environments)
             let tray_available = match tray::setup_tray(app) {
                 Ok(_) => true,
@@ -22,9 +25,10 @@ pub fn run() {
                     false
                 }
             };
+            *app.state::<state::AppState>().tray_available.lock().unwrap() = tray_available;
 
-            // Initialize state with tray availability
-            app.manage(state::AppState::new(loaded_settings, tray_available));
+            // Open the microphone ahead of time if the warm mic is on
+            tray::apply_warm_mic(app.handle());
 
             // Setup global shortcuts (hybrid: Tauri plugin / Portal / CLI fallback)
             shortcuts::setup_shortcuts(app);
@@ -53,6 +57,11 @@ pub fn run() {
             commands::portal_bind_error,
             commands::get_toggle_command,
             commands::toggle_recording,
+            commands::cancel_transcription,
+            commands::discard_recording,
+            commands::toggle_pause,
+            commands::list_input_devices,
+            commands::set_input_device,
             commands::can_reopen_window,
         ])
         .run(tauri::generate_context!())
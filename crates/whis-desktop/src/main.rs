@@ -24,6 +24,33 @@ fn main() {
         return;
     }
 
+    // Handle --cancel: cancel the transcription in progress in the running instance
+    if args.contains(&"--cancel".to_string()) {
+        if let Err(e) = whis_desktop::shortcuts::send_cancel_command() {
+            eprintln!("Failed to cancel: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Handle --discard: throw away the recording in progress in the running instance
+    if args.contains(&"--discard".to_string()) {
+        if let Err(e) = whis_desktop::shortcuts::send_discard_command() {
+            eprintln!("Failed to discard: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // Handle --pause: pause the recording in progress in the running instance, or resume it
+    if args.contains(&"--pause".to_string()) {
+        if let Err(e) = whis_desktop::shortcuts::send_pause_command() {
+            eprintln!("Failed to pause: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     // Handle --install: create .desktop file for proper app_id on Wayland
     if args.contains(&"--install".to_string()) {
         install_desktop_file();
@@ -45,12 +72,18 @@ fn main() {
         println!();
         println!("OPTIONS:");
         println!("    -t, --toggle    Toggle recording in running instance");
+        println!("        --cancel    Cancel the transcription in progress");
+        println!("        --discard   Discard the recording in progress");
+        println!("        --pause     Pause the recording in progress, or resume it");
         println!("        --install   Install desktop file and icons for app menu");
         println!("        --uninstall Remove desktop file and icons");
         println!("    -h, --help      Print this help message");
         println!();
         println!("GLOBAL SHORTCUT:");
         println!("    Ctrl+Shift+R    Toggle recording (X11/Portal only)");
+        println!("    Ctrl+Shift+X    Cancel transcription (X11/Portal only)");
+        println!("    Ctrl+Shift+D    Discard recording (X11/Portal only)");
+        println!("    Ctrl+Shift+P    Pause/resume recording (X11/Portal only)");
         println!();
         println!("For Wayland without portal support, configure your compositor");
         println!("to run 'whis-desktop --toggle' on your preferred shortcut.");
@@ -15,6 +15,68 @@ pub enum ShortcutBackend {
     ManualSetup,
 }
 
+/// Shortcuts besides the recording toggle
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SecondaryShortcut {
+    /// Abort the transcription in progress
+    Cancel,
+    /// Throw away the current recording without transcribing
+    Discard,
+    /// Pause the current recording, or resume it
+    Pause,
+}
+
+impl SecondaryShortcut {
+    const ALL: [Self; 3] = [Self::Cancel, Self::Discard, Self::Pause];
+
+    /// Portal shortcut id, also used as IPC command
+    fn id(self) -> &'static str {
+        match self {
+            Self::Cancel => "cancel-transcription",
+            Self::Discard => "discard-recording",
+            Self::Pause => "pause-recording",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            Self::Cancel => "Cancel transcription",
+            Self::Discard => "Discard recording",
+            Self::Pause => "Pause or resume recording",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.id() == id)
+    }
+
+    /// Configured trigger, e.g. "Ctrl+Shift+X"
+    fn trigger(self, app: &AppHandle) -> String {
+        let state = app.state::<crate::state::AppState>();
+        let settings = state.settings.lock().unwrap();
+        match self {
+            Self::Cancel => settings.cancel_shortcut.clone(),
+            Self::Discard => settings.discard_shortcut.clone(),
+            Self::Pause => settings.pause_shortcut.clone(),
+        }
+    }
+
+    fn run(self, app: AppHandle) {
+        match self {
+            Self::Cancel => crate::tray::cancel_transcription(app),
+            Self::Discard => crate::tray::discard_recording(app),
+            Self::Pause => crate::tray::toggle_pause(app),
+        }
+    }
+
+    /// Portal shortcut definition with the configured trigger
+    fn portal_shortcut(self, app: &AppHandle) -> ashpd::desktop::global_shortcuts::NewShortcut {
+        let xdg_trigger = convert_to_xdg_format(&self.trigger(app));
+        ashpd::desktop::global_shortcuts::NewShortcut::new(self.id(), self.description())
+            .preferred_trigger(Some(xdg_trigger.as_str()))
+    }
+}
+
 /// Information about shortcut capability on current system
 pub struct ShortcutCapability {
     pub backend: ShortcutBackend,
@@ -169,12 +231,12 @@ pub fn read_portal_shortcut_from_dconf() -> Option<String> {
     for line in dump.lines() {
         if line.contains("toggle-recording") && line.contains("shortcuts") {
             // Parse the GVariant format: <['<Control><Alt>m']>
-            if let Some(start) = line.find("<['") {
-                if let Some(end) = line[start..].find("']>") {
-                    let raw = &line[start + 3..start + end];
-                    // Convert <Control><Alt>m to Ctrl+Alt+M
-                    return Some(convert_gvariant_shortcut(raw));
-                }
+            if let Some(start) = line.find("<['")
+                && let Some(end) = line[start..].find("']>")
+            {
+                let raw = &line[start + 3..start + end];
+                // Convert <Control><Alt>m to Ctrl+Alt+M
+                return Some(convert_gvariant_shortcut(raw));
             }
         }
     }
@@ -228,34 +290,47 @@ where
     let session = shortcuts.create_session().await?;
 
     // Check for existing shortcuts first
-    if let Ok(list_request) = shortcuts.list_shortcuts(&session).await {
-        if let Ok(list_response) = list_request.response() {
-            let existing = list_response.shortcuts();
-            if let Some(s) = existing.iter().find(|s| s.id() == "toggle-recording") {
-                let trigger = s.trigger_description().to_string();
-                println!("Found existing portal shortcut in session: {trigger}");
-                let state = app_handle.state::<crate::state::AppState>();
-                *state.portal_shortcut.lock().unwrap() = Some(trigger);
-                // Skip binding, just listen for activations
-                let mut activated = shortcuts.receive_activated().await?;
-                while let Some(event) = activated.next().await {
-                    if event.shortcut_id() == "toggle-recording" {
+    if let Ok(list_request) = shortcuts.list_shortcuts(&session).await
+        && let Ok(list_response) = list_request.response()
+    {
+        let existing = list_response.shortcuts();
+        if let Some(s) = existing.iter().find(|s| s.id() == "toggle-recording") {
+            let trigger = s.trigger_description().to_string();
+            println!("Found existing portal shortcut in session: {trigger}");
+            let state = app_handle.state::<crate::state::AppState>();
+            *state.portal_shortcut.lock().unwrap() = Some(trigger);
+            // Skip binding, just listen for activations
+            let mut activated = shortcuts.receive_activated().await?;
+            while let Some(event) = activated.next().await {
+                match event.shortcut_id() {
+                    "toggle-recording" => {
                         println!("Portal shortcut triggered!");
                         on_toggle();
                     }
+                    id => {
+                        if let Some(secondary) = SecondaryShortcut::from_id(id) {
+                            secondary.run(app_handle.clone());
+                        }
+                    }
                 }
-                return Ok(());
             }
+            return Ok(());
         }
     }
 
-    // Define the toggle-recording shortcut
-    let shortcut = NewShortcut::new("toggle-recording", "Toggle voice recording")
-        .preferred_trigger(Some(shortcut_str.as_str()));
+    // Define the toggle-recording shortcut, followed by cancel, discard and pause
+    let mut new_shortcuts = vec![
+        NewShortcut::new("toggle-recording", "Toggle voice recording")
+            .preferred_trigger(Some(shortcut_str.as_str())),
+    ];
+    new_shortcuts.extend(SecondaryShortcut::ALL.map(|s| s.portal_shortcut(&app_handle)));
 
     // Try to bind - pass None for parent window (GNOME may show dialog to user)
     // Note: GNOME shows a configuration dialog that user must interact with
-    match shortcuts.bind_shortcuts(&session, &[shortcut], None).await {
+    match shortcuts
+        .bind_shortcuts(&session, &new_shortcuts, None)
+        .await
+    {
         Ok(request) => {
             match request.response() {
                 Ok(bind_response) => {
@@ -294,15 +369,23 @@ where
     // Listen for activations (this should still work even if bind failed)
     let mut activated = shortcuts.receive_activated().await?;
     while let Some(event) = activated.next().await {
-        if event.shortcut_id() == "toggle-recording" {
-            println!("Portal shortcut triggered!");
-            on_toggle();
+        match event.shortcut_id() {
+            "toggle-recording" => {
+                println!("Portal shortcut triggered!");
+                on_toggle();
+            }
+            id => {
+                if let Some(secondary) = SecondaryShortcut::from_id(id) {
+                    secondary.run(app_handle.clone());
+                }
+            }
         }
     }
 
     Ok(())
 }
 
+
 /// Open the system's shortcut configuration dialog (Portal v2+ only)
 /// Falls back to direct binding on Portal v1
 pub async fn open_configure_shortcuts(
@@ -333,18 +416,18 @@ pub async fn bind_shortcut_with_trigger(
     let session = shortcuts.create_session().await?;
 
     // Check for existing shortcuts first (XDG spec: can only bind once per session)
-    if let Ok(list_request) = shortcuts.list_shortcuts(&session).await {
-        if let Ok(list_response) = list_request.response() {
-            let existing = list_response.shortcuts();
-            if !existing.is_empty() {
-                println!("Found {} existing shortcut(s) in session", existing.len());
-                if let Some(s) = existing.iter().find(|s| s.id() == "toggle-recording") {
-                    let trigger = s.trigger_description().to_string();
-                    println!("Using existing shortcut: {trigger}");
-                    let state = app_handle.state::<crate::state::AppState>();
-                    *state.portal_shortcut.lock().unwrap() = Some(trigger.clone());
-                    return Ok(Some(trigger));
-                }
+    if let Ok(list_request) = shortcuts.list_shortcuts(&session).await
+        && let Ok(list_response) = list_request.response()
+    {
+        let existing = list_response.shortcuts();
+        if !existing.is_empty() {
+            println!("Found {} existing shortcut(s) in session", existing.len());
+            if let Some(s) = existing.iter().find(|s| s.id() == "toggle-recording") {
+                let trigger = s.trigger_description().to_string();
+                println!("Using existing shortcut: {trigger}");
+                let state = app_handle.state::<crate::state::AppState>();
+                *state.portal_shortcut.lock().unwrap() = Some(trigger.clone());
+                return Ok(Some(trigger));
             }
         }
     }
@@ -360,8 +443,14 @@ pub async fn bind_shortcut_with_trigger(
 
     println!("Binding shortcut...");
 
+    // Bind cancel, discard and pause in the same request, a session can only bind once
+    let mut new_shortcuts = vec![shortcut];
+    new_shortcuts.extend(SecondaryShortcut::ALL.map(|s| s.portal_shortcut(&app_handle)));
+
     // Try to bind the shortcut (GNOME shows a dialog that user must interact with)
-    let bind_result = shortcuts.bind_shortcuts(&session, &[shortcut], None).await;
+    let bind_result = shortcuts
+        .bind_shortcuts(&session, &new_shortcuts, None)
+        .await;
 
     match bind_result {
         Ok(request) => {
@@ -380,20 +469,20 @@ pub async fn bind_shortcut_with_trigger(
                         let _ = shortcuts.configure_shortcuts(&session, None, None).await;
 
                         // Re-query after configure in case user changed it
-                        if let Ok(list_request) = shortcuts.list_shortcuts(&session).await {
-                            if let Ok(list_response) = list_request.response() {
-                                let updated_trigger = list_response
-                                    .shortcuts()
-                                    .iter()
-                                    .find(|s| s.id() == "toggle-recording")
-                                    .map(|s| s.trigger_description().to_string());
-
-                                if let Some(ref t) = updated_trigger {
-                                    let state = app_handle.state::<crate::state::AppState>();
-                                    *state.portal_shortcut.lock().unwrap() = Some(t.clone());
-                                    println!("Portal shortcut configured to: {t}");
-                                    return Ok(updated_trigger);
-                                }
+                        if let Ok(list_request) = shortcuts.list_shortcuts(&session).await
+                            && let Ok(list_response) = list_request.response()
+                        {
+                            let updated_trigger = list_response
+                                .shortcuts()
+                                .iter()
+                                .find(|s| s.id() == "toggle-recording")
+                                .map(|s| s.trigger_description().to_string());
+
+                            if let Some(ref t) = updated_trigger {
+                                let state = app_handle.state::<crate::state::AppState>();
+                                *state.portal_shortcut.lock().unwrap() = Some(t.clone());
+                                println!("Portal shortcut configured to: {t}");
+                                return Ok(updated_trigger);
                             }
                         }
                     }
@@ -452,13 +541,21 @@ pub fn setup_tauri_shortcut(app: &tauri::App, shortcut_str: &str) -> Result<(),
     // Attempt to parse the shortcut
     let shortcut = Shortcut::from_str(shortcut_str).map_err(|e| format!("Invalid shortcut: {e}"))?;
 
-    // Initialize plugin with generic handler
+    // Initialize plugin with generic handler: cancel, discard and pause shortcuts run their
+    // action, anything else toggles recording
     app.handle().plugin(
         tauri_plugin_global_shortcut::Builder::new()
-            .with_handler(move |_app, _shortcut, event| {
+            .with_handler(move |_app, pressed, event| {
                 if event.state() == ShortcutState::Pressed {
-                    println!("Tauri shortcut triggered!");
                     let handle = app_handle.clone();
+                    let secondary = SecondaryShortcut::ALL.into_iter().find(|s| {
+                        Shortcut::from_str(&s.trigger(&handle)).is_ok_and(|s| s == *pressed)
+                    });
+                    if let Some(secondary) = secondary {
+                        secondary.run(handle);
+                        return;
+                    }
+                    println!("Tauri shortcut triggered!");
                     tauri::async_runtime::spawn(async move {
                         crate::tray::toggle_recording_public(handle);
                     });
@@ -470,10 +567,30 @@ pub fn setup_tauri_shortcut(app: &tauri::App, shortcut_str: &str) -> Result<(),
     // Register the shortcut
     app.global_shortcut().register(shortcut)?;
     println!("Tauri global shortcut registered: {shortcut_str}");
+    register_secondary_shortcuts(app.handle());
 
     Ok(())
 }
 
+/// Register the cancel, discard and pause shortcuts. Failing is not fatal since the
+/// tray and `--cancel`/`--discard`/`--pause` offer the same actions.
+fn register_secondary_shortcuts(app: &AppHandle) {
+    for secondary in SecondaryShortcut::ALL {
+        let trigger = secondary.trigger(app);
+        let result = Shortcut::from_str(&trigger)
+            .map_err(|e| e.to_string())
+            .and_then(|shortcut| {
+                app.global_shortcut()
+                    .register(shortcut)
+                    .map_err(|e| e.to_string())
+            });
+        match result {
+            Ok(()) => println!("Tauri {secondary:?} shortcut registered: {trigger}"),
+            Err(e) => eprintln!("Failed to register {secondary:?} shortcut {trigger}: {e}"),
+        }
+    }
+}
+
 /// Setup shortcuts based on detected backend
 pub fn setup_shortcuts(app: &tauri::App) {
     let capability = detect_backend();
@@ -536,6 +653,9 @@ pub fn update_shortcut(app: &AppHandle, new_shortcut: &str) -> Result<bool, Box<
             let shortcut = Shortcut::from_str(new_shortcut).map_err(|e| format!("Invalid shortcut: {e}"))?;
             app.global_shortcut().register(shortcut)?;
             println!("Updated Tauri global shortcut to: {new_shortcut}");
+
+            // unregister_all also removed the cancel, discard and pause shortcuts
+            register_secondary_shortcuts(app);
             Ok(false) // No restart needed
         },
         _ => {
@@ -546,6 +666,41 @@ pub fn update_shortcut(app: &AppHandle, new_shortcut: &str) -> Result<bool, Box<
     }
 }
 
+/// Re-register the cancel, discard and pause shortcuts after they changed.
+/// `old_triggers` are the triggers registered before the change.
+/// Returns Ok(true) if restart is needed, Ok(false) if applied immediately.
+pub fn update_secondary_shortcuts(
+    app: &AppHandle,
+    old_triggers: &[String],
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let capability = detect_backend();
+
+    match capability.backend {
+        ShortcutBackend::TauriPlugin => {
+            let toggle = {
+                let state = app.state::<crate::state::AppState>();
+                let settings = state.settings.lock().unwrap();
+                Shortcut::from_str(&settings.shortcut).ok()
+            };
+            for trigger in old_triggers {
+                let Ok(shortcut) = Shortcut::from_str(trigger) else {
+                    continue;
+                };
+                // Never drop the recording shortcut if it reuses an old trigger
+                if Some(shortcut) != toggle && app.global_shortcut().is_registered(shortcut) {
+                    app.global_shortcut().unregister(shortcut)?;
+                }
+            }
+            register_secondary_shortcuts(app);
+            Ok(false)
+        }
+        _ => {
+            println!("Shortcuts saved. Restart required for changes to take effect.");
+            Ok(true)
+        }
+    }
+}
+
 
 fn print_manual_setup_instructions(compositor: &str, shortcut: &str) {
     println!();
@@ -578,10 +733,33 @@ fn print_manual_setup_instructions(compositor: &str, shortcut: &str) {
         }
     }
     println!();
+    println!("To cancel a transcription in progress, bind: whis-desktop --cancel");
+    println!("To discard a recording without transcribing, bind: whis-desktop --discard");
+    println!("To pause or resume a recording, bind: whis-desktop --pause");
+    println!();
 }
 
 /// Send toggle command to running instance via Unix socket
 pub fn send_toggle_command() -> Result<(), Box<dyn std::error::Error>> {
+    send_command("toggle")
+}
+
+/// Send cancel command to running instance via Unix socket
+pub fn send_cancel_command() -> Result<(), Box<dyn std::error::Error>> {
+    send_command("cancel")
+}
+
+/// Send discard command to running instance via Unix socket
+pub fn send_discard_command() -> Result<(), Box<dyn std::error::Error>> {
+    send_command("discard")
+}
+
+/// Send pause command (pause or resume) to running instance via Unix socket
+pub fn send_pause_command() -> Result<(), Box<dyn std::error::Error>> {
+    send_command("pause")
+}
+
+fn send_command(command: &str) -> Result<(), Box<dyn std::error::Error>> {
     use std::io::Write;
     use std::os::unix::net::UnixStream;
 
@@ -589,8 +767,8 @@ pub fn send_toggle_command() -> Result<(), Box<dyn std::error::Error>> {
 
     match UnixStream::connect(&socket_path) {
         Ok(mut stream) => {
-            stream.write_all(b"toggle")?;
-            println!("Toggle command sent");
+            stream.write_all(command.as_bytes())?;
+            println!("{command} command sent");
             Ok(())
         }
         Err(e) => {
@@ -628,13 +806,28 @@ pub fn start_ipc_listener(app_handle: AppHandle) {
                     let mut buf = [0u8; 64];
                     if let Ok(n) = stream.read(&mut buf) {
                         let cmd = String::from_utf8_lossy(&buf[..n]);
-                        if cmd.trim() == "toggle" {
-                            println!("IPC: toggle command received");
-                            let handle = app_handle.clone();
-                            // Dispatch to Tauri's async runtime - the IPC thread has no Tokio runtime
-                            tauri::async_runtime::spawn(async move {
-                                crate::tray::toggle_recording_public(handle);
-                            });
+                        match cmd.trim() {
+                            "toggle" => {
+                                println!("IPC: toggle command received");
+                                let handle = app_handle.clone();
+                                // Dispatch to Tauri's async runtime - the IPC thread has no Tokio runtime
+                                tauri::async_runtime::spawn(async move {
+                                    crate::tray::toggle_recording_public(handle);
+                                });
+                            }
+                            "cancel" => {
+                                println!("IPC: cancel command received");
+                                crate::tray::cancel_transcription(app_handle.clone());
+                            }
+                            "discard" => {
+                                println!("IPC: discard command received");
+                                crate::tray::discard_recording(app_handle.clone());
+                            }
+                            "pause" => {
+                                println!("IPC: pause command received");
+                                crate::tray::toggle_pause(app_handle.clone());
+                            }
+                            _ => {}
                         }
                     }
                 }
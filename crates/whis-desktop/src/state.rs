@@ -1,20 +1,35 @@
-use std::sync::Mutex;
-use tauri::menu::MenuItem;
-use whis_core::{AudioRecorder, ApiConfig};
+use std::sync::{Arc, Mutex};
+use tauri::async_runtime::JoinHandle;
+use tauri::menu::{CheckMenuItem, MenuItem, Submenu};
+use whis_core::{AudioRecorder, CancellationToken, Transcript, TranscriptionProvider};
 use crate::settings::Settings;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum RecordingState {
     Idle,
     Recording,
+    /// Recording in progress, but no audio is added until it resumes
+    Paused,
     Transcribing,
 }
 
 pub struct AppState {
     pub state: Mutex<RecordingState>,
     pub recorder: Mutex<Option<AudioRecorder>>,
-    pub api_config: Mutex<Option<ApiConfig>>,
+    /// Transcription provider, built from settings on first use
+    pub provider: Mutex<Option<Arc<dyn TranscriptionProvider>>>,
     pub record_menu_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+    pub cancel_menu_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+    pub discard_menu_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+    pub pause_menu_item: Mutex<Option<MenuItem<tauri::Wry>>>,
+    /// Microphone picker in the tray, rebuilt when devices are listed
+    pub device_menu: Mutex<Option<Submenu<tauri::Wry>>>,
+    /// Tray switch for the warm mic, checked while the microphone is kept open
+    pub warm_menu_item: Mutex<Option<CheckMenuItem<tauri::Wry>>>,
+    /// Cancels the transcription in progress
+    pub cancel: Mutex<Option<CancellationToken>>,
+    /// Transcribes the current recording chunk by chunk while it is recorded
+    pub session: Mutex<Option<JoinHandle<whis_core::Result<Transcript>>>>,
     pub settings: Mutex<Settings>,
     /// The actual shortcut binding from the XDG Portal (Wayland only)
     pub portal_shortcut: Mutex<Option<String>>,
@@ -29,8 +44,15 @@ impl AppState {
         Self {
             state: Mutex::new(RecordingState::Idle),
             recorder: Mutex::new(None),
-            api_config: Mutex::new(None),
+            provider: Mutex::new(None),
             record_menu_item: Mutex::new(None),
+            cancel_menu_item: Mutex::new(None),
+            discard_menu_item: Mutex::new(None),
+            pause_menu_item: Mutex::new(None),
+            device_menu: Mutex::new(None),
+            warm_menu_item: Mutex::new(None),
+            cancel: Mutex::new(None),
+            session: Mutex::new(None),
             settings: Mutex::new(settings),
             portal_shortcut: Mutex::new(None),
             portal_bind_error: Mutex::new(None),
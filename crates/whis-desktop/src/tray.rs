@@ -1,12 +1,13 @@
 use crate::state::{AppState, RecordingState};
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::TrayIconBuilder,
-    AppHandle, Manager, WebviewWindowBuilder, WebviewUrl,
+    AppHandle, Emitter, Manager, WebviewWindowBuilder, WebviewUrl,
 };
 use whis_core::{
-    copy_to_clipboard, parallel_transcribe, transcribe_audio, AudioRecorder, RecordingOutput, ApiConfig,
+    copy_to_clipboard, source, transcribe_while_recording, AudioRecorder, AutoStop,
+    CancellationToken, EncodingOptions, InputDevice, TranscriptionOptions, WhisError,
 };
 
 // Static icons for each state (pre-loaded at compile time)
@@ -16,10 +17,34 @@ const ICON_TRANSCRIBING: &[u8] = include_bytes!("../icons/icon-processing.png");
 
 pub const TRAY_ID: &str = "whis-tray";
 
+/// Event carrying the input level and elapsed time while recording (for the level meter)
+pub const INPUT_LEVEL_EVENT: &str = "input-level";
+
+/// Menu id prefix of the microphone entries, followed by the device name
+const DEVICE_ID_PREFIX: &str = "device:";
+/// Menu id of the system default microphone entry
+const DEFAULT_DEVICE_ID: &str = "device-default";
+/// Menu id of the warm mic switch
+const WARM_MIC_ID: &str = "warm-mic";
+/// Pre-roll used when the warm mic is switched on from the tray
+const DEFAULT_PRE_ROLL_MS: u32 = 500;
+
 
 pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     // Create menu items
     let record = MenuItem::with_id(app, "record", "Start Recording", true, None::<&str>)?;
+    let pause = MenuItem::with_id(app, "pause", "Pause Recording", false, None::<&str>)?;
+    let discard = MenuItem::with_id(app, "discard", "Discard Recording", false, None::<&str>)?;
+    let cancel = MenuItem::with_id(app, "cancel", "Cancel Transcription", false, None::<&str>)?;
+    let devices = Submenu::with_id(app, "devices", "Microphone", true)?;
+    let warm = CheckMenuItem::with_id(
+        app,
+        WARM_MIC_ID,
+        "Warm Mic (keeps microphone open)",
+        true,
+        false,
+        None::<&str>,
+    )?;
     let settings = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
     let sep = PredefinedMenuItem::separator(app)?;
     let quit = MenuItem::with_id(app, "quit", "Quit Whis", true, None::<&str>)?;
@@ -27,9 +52,20 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     // Store the record menu item for later updates
     if let Some(state) = app.try_state::<AppState>() {
         *state.record_menu_item.lock().unwrap() = Some(record.clone());
+        *state.pause_menu_item.lock().unwrap() = Some(pause.clone());
+        *state.discard_menu_item.lock().unwrap() = Some(discard.clone());
+        *state.cancel_menu_item.lock().unwrap() = Some(cancel.clone());
+        *state.device_menu.lock().unwrap() = Some(devices.clone());
+        *state.warm_menu_item.lock().unwrap() = Some(warm.clone());
     }
+    refresh_device_menu(app.handle());
 
-    let menu = Menu::with_items(app, &[&record, &sep, &settings, &sep, &quit])?;
+    let menu = Menu::with_items(
+        app,
+        &[
+            &record, &pause, &discard, &cancel, &sep, &devices, &warm, &sep, &settings, &sep, &quit,
+        ],
+    )?;
 
     // Use image crate for consistent rendering (same as set_tray_icon)
     let idle_bytes = include_bytes!("../icons/icon-idle.png");
@@ -55,23 +91,46 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
                     toggle_recording(app_clone);
                 });
             }
+            "pause" => {
+                toggle_pause(app.clone());
+            }
+            "discard" => {
+                discard_recording(app.clone());
+            }
+            "cancel" => {
+                cancel_transcription(app.clone());
+            }
             "settings" => {
                 open_settings_window(app.clone());
             }
+            WARM_MIC_ID => {
+                toggle_warm_mic(app);
+            }
             "quit" => {
                 app.exit(0);
             }
-            _ => {}
+            DEFAULT_DEVICE_ID => {
+                if let Err(e) = select_input_device(app, None) {
+                    eprintln!("Failed to select microphone: {e}");
+                }
+            }
+            id => {
+                if let Some(name) = id.strip_prefix(DEVICE_ID_PREFIX)
+                    && let Err(e) = select_input_device(app, Some(name.to_string()))
+                {
+                    eprintln!("Failed to select microphone: {e}");
+                }
+            }
         })
         .on_tray_icon_event(|tray, event| {
             use tauri::tray::TrayIconEvent;
-            if let TrayIconEvent::Click { button, .. } = event {
-                if button == tauri::tray::MouseButton::Left {
-                    let app_handle = tray.app_handle().clone();
-                    tauri::async_runtime::spawn(async move {
-                        toggle_recording(app_handle);
-                    });
-                }
+            if let TrayIconEvent::Click { button, .. } = event
+                && button == tauri::tray::MouseButton::Left
+            {
+                let app_handle = tray.app_handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    toggle_recording(app_handle);
+                });
             }
         })
         .build(app)?;
@@ -123,11 +182,12 @@ fn toggle_recording(app: AppHandle) {
                 eprintln!("Failed to start recording: {e}");
             }
         }
-        RecordingState::Recording => {
-            // Stop recording and transcribe
+        RecordingState::Recording | RecordingState::Paused => {
+            // Stop recording and transcribe (a paused recording ends where it was paused)
             let app_clone = app.clone();
             tauri::async_runtime::spawn(async move {
-                if let Err(e) = stop_and_transcribe(&app_clone).await {
+                let from = [RecordingState::Recording, RecordingState::Paused];
+                if let Err(e) = stop_and_transcribe(&app_clone, &from).await {
                     eprintln!("Failed to transcribe: {e}");
                 }
             });
@@ -139,131 +199,482 @@ fn toggle_recording(app: AppHandle) {
 }
 
 fn start_recording_sync(app: &AppHandle, state: &AppState) -> Result<(), String> {
-    // Load API config if not already loaded
-    {
-        let mut config_guard = state.api_config.lock().unwrap();
-        if config_guard.is_none() {
-            // Try settings first, then environment variable
-            let api_key = {
-                let settings = state.settings.lock().unwrap();
-                settings.openai_api_key.clone()
-            }
-            .or_else(|| std::env::var("OPENAI_API_KEY").ok());
-
-            let api_key = api_key.ok_or(
-                "No API key configured. Add it in Settings > API Keys.",
-            )?;
-
-            *config_guard = Some(ApiConfig { openai_api_key: api_key });
+    // Load transcription provider if not already loaded
+    let provider = {
+        let mut provider_guard = state.provider.lock().unwrap();
+        if provider_guard.is_none() {
+            // Local model if selected, otherwise API key from settings or environment
+            let settings = state.settings.lock().unwrap().clone();
+            let provider =
+                whis_core::provider::from_settings(&settings).map_err(|e| user_message(&e))?;
+
+            *provider_guard = Some(provider);
         }
-    }
+        provider_guard.clone().ok_or("Transcription provider not loaded")?
+    };
 
-    // Start recording
-    let mut recorder = AudioRecorder::new().map_err(|e| e.to_string())?;
-    recorder.start_recording().map_err(|e| e.to_string())?;
+    // Start recording from the selected microphone (falls back to the default one);
+    // a warm microphone is already open and holds the audio from just before now
+    let warm = state.recorder.lock().unwrap().take();
+    let mut recorder = match warm {
+        Some(recorder) => recorder,
+        None => AudioRecorder::new().map_err(|e| user_message(&e))?,
+    };
+    let audio = recorder.stream_samples();
+    let mut levels = recorder.watch_levels();
+    let (auto_stop, options, encoding) = {
+        let settings = state.settings.lock().unwrap();
+        recorder.set_source(source::from_settings(&settings).map_err(|e| user_message(&e))?);
+        (
+            AutoStop::from_settings(&settings),
+            TranscriptionOptions::from_settings(&settings),
+            EncodingOptions::from_settings(&settings),
+        )
+    };
+    let silence = auto_stop.map(|auto_stop| recorder.watch_silence(auto_stop));
+    recorder.start_recording().map_err(|e| user_message(&e))?;
+
+    // Completed chunks are encoded and transcribed in the background
+    let cancel = CancellationToken::new();
+    let (sample_rate, channels) = (recorder.sample_rate(), recorder.channels());
+    let session = tauri::async_runtime::spawn({
+        let cancel = cancel.clone();
+        async move {
+            transcribe_while_recording(
+                provider,
+                audio,
+                sample_rate,
+                channels,
+                &options,
+                encoding,
+                None,
+                &cancel,
+            )
+            .await
+        }
+    });
 
     *state.recorder.lock().unwrap() = Some(recorder);
+    *state.cancel.lock().unwrap() = Some(cancel);
+    *state.session.lock().unwrap() = Some(session);
     *state.state.lock().unwrap() = RecordingState::Recording;
 
     // Update tray
     update_tray(app, RecordingState::Recording);
     println!("Recording started...");
 
+    // Forward input levels to the window; the channel closes when recording stops
+    {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            while let Some(level) = levels.recv().await {
+                let _ = app.emit(INPUT_LEVEL_EVENT, level);
+            }
+        });
+    }
+
+    // Stop hands-free once the speaker has gone quiet
+    if let Some(silence) = silence {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            // Fails when the recording ended some other way first
+            if silence.await.is_err() {
+                return;
+            }
+            println!("Silence detected, stopping...");
+            // A paused recording is left alone; one already stopped is not stopped again
+            if let Err(e) = stop_and_transcribe(&app, &[RecordingState::Recording]).await {
+                eprintln!("Failed to transcribe: {e}");
+            }
+        });
+    }
+
     Ok(())
 }
 
-async fn stop_and_transcribe(app: &AppHandle) -> Result<(), String> {
+/// Stop the recording and transcribe it if it is in one of the `from` states. Does
+/// nothing if another caller (toggle, silence auto-stop) already took it over.
+async fn stop_and_transcribe(app: &AppHandle, from: &[RecordingState]) -> Result<(), String> {
     let state = app.state::<AppState>();
 
-    // Update state to transcribing
-    {
-        *state.state.lock().unwrap() = RecordingState::Transcribing;
-    }
+    // Move to transcribing in one step, so only one caller owns the transition
+    let cancel = {
+        let mut current_state = state.state.lock().unwrap();
+        if !from.contains(&current_state) {
+            return Ok(());
+        }
+        *current_state = RecordingState::Transcribing;
+        state.cancel.lock().unwrap().clone().unwrap_or_default()
+    };
     update_tray(app, RecordingState::Transcribing);
     println!("Transcribing...");
 
-    // Get recorder and config
-    let mut recorder = state
+    let result = finish_transcription(&state, &cancel).await;
+
+    // Reset state on success and failure alike
+    {
+        let mut current_state = state.state.lock().unwrap();
+        if cancel.is_cancelled() {
+            // cancel_transcription already reset the state (maybe even recording again)
+            return Ok(());
+        }
+        release_recorder(&state);
+        state.session.lock().unwrap().take();
+        *state.cancel.lock().unwrap() = None;
+        *current_state = RecordingState::Idle;
+    }
+    update_tray(app, RecordingState::Idle);
+
+    let transcription = result?;
+    println!("Done: {}", &transcription[..transcription.len().min(50)]);
+
+    Ok(())
+}
+
+/// Stop the recording, wait for the transcript and copy it to the clipboard
+async fn finish_transcription(
+    state: &AppState,
+    cancel: &CancellationToken,
+) -> Result<String, String> {
+    // Stop recording; closing the sample stream sends off the last chunk
+    state
         .recorder
         .lock()
         .unwrap()
+        .as_mut()
+        .ok_or("No active recording")?
+        .stop_stream();
+    release_recorder(state);
+    let session = state
+        .session
+        .lock()
+        .unwrap()
         .take()
         .ok_or("No active recording")?;
 
-    let api_key = state
-        .api_config
+    // Wait for the chunks still in flight (single file, parallel chunks or local PCM)
+    let transcription = match session.await.map_err(|e| e.to_string())? {
+        Ok(transcript) => transcript.text,
+        Err(e) => return Err(user_message(&e)),
+    };
+    if cancel.is_cancelled() {
+        return Err(user_message(&WhisError::Cancelled));
+    }
+
+    // Copy to clipboard
+    copy_to_clipboard(&transcription).map_err(|e| user_message(&e))?;
+
+    Ok(transcription)
+}
+
+/// Abort the transcription in progress and go back to idle without touching the clipboard
+pub fn cancel_transcription(app: AppHandle) {
+    let state = app.state::<AppState>();
+    {
+        let mut current_state = state.state.lock().unwrap();
+        if *current_state != RecordingState::Transcribing {
+            return;
+        }
+        if let Some(cancel) = state.cancel.lock().unwrap().take() {
+            cancel.cancel();
+        }
+        *current_state = RecordingState::Idle;
+    }
+    update_tray(&app, RecordingState::Idle);
+    println!("Transcription cancelled");
+}
+
+/// Throw away the recording in progress and go back to idle without calling the API
+pub fn discard_recording(app: AppHandle) {
+    let state = app.state::<AppState>();
+    {
+        let mut current_state = state.state.lock().unwrap();
+        if !matches!(*current_state, RecordingState::Recording | RecordingState::Paused) {
+            return;
+        }
+        if let Some(recorder) = state.recorder.lock().unwrap().as_mut() {
+            recorder.discard_recording();
+        }
+        release_recorder(&state);
+        // Chunks already sent off are abandoned
+        if let Some(cancel) = state.cancel.lock().unwrap().take() {
+            cancel.cancel();
+        }
+        state.session.lock().unwrap().take();
+        *current_state = RecordingState::Idle;
+    }
+    update_tray(&app, RecordingState::Idle);
+    println!("Recording discarded");
+}
+
+/// Pause the recording in progress, keeping what was recorded, or resume it if paused
+pub fn toggle_pause(app: AppHandle) {
+    let state = app.state::<AppState>();
+    let new_state = {
+        let mut current_state = state.state.lock().unwrap();
+        let mut recorder = state.recorder.lock().unwrap();
+        let Some(recorder) = recorder.as_mut() else {
+            return;
+        };
+        let result = match *current_state {
+            RecordingState::Recording => recorder.pause().map(|_| RecordingState::Paused),
+            RecordingState::Paused => recorder.resume().map(|_| RecordingState::Recording),
+            RecordingState::Idle | RecordingState::Transcribing => return,
+        };
+        match result {
+            Ok(new_state) => {
+                *current_state = new_state;
+                new_state
+            }
+            Err(e) => {
+                eprintln!("Failed to pause recording: {e}");
+                return;
+            }
+        }
+    };
+    update_tray(&app, new_state);
+    if new_state == RecordingState::Paused {
+        println!("Recording paused");
+    } else {
+        println!("Recording resumed");
+    }
+}
+
+/// Save the microphone to record from (`None` for the system default)
+pub fn select_input_device(app: &AppHandle, name: Option<String>) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    {
+        let mut settings = state.settings.lock().unwrap();
+        settings.input_device = name;
+        settings.save().map_err(|e| e.to_string())?;
+    }
+    refresh_device_menu(app);
+    // A warm microphone has to be reopened to switch devices
+    apply_warm_mic(app);
+    Ok(())
+}
+
+/// Drop the recorder after a recording, unless it keeps the microphone warm
+fn release_recorder(state: &AppState) {
+    let mut recorder = state.recorder.lock().unwrap();
+    if !recorder.as_ref().is_some_and(AudioRecorder::is_warm) {
+        *recorder = None;
+    }
+}
+
+/// Whether the microphone is kept open between recordings
+pub fn is_warm(state: &AppState) -> bool {
+    state
+        .recorder
         .lock()
         .unwrap()
         .as_ref()
-        .ok_or("API config not loaded")?
-        .openai_api_key
-        .clone();
-
-    // Finalize recording (synchronous file encoding)
-    // Note: AudioRecorder might need to be Send to be moved into async block?
-    // It is likely Send since it's in a Mutex.
-    let audio_result = recorder.finalize_recording().map_err(|e| e.to_string())?;
-
-    // Transcribe
-    let transcription = match audio_result {
-        // transcribe_audio is synchronous (blocking HTTP), so we should wrap it in spawn_blocking
-        // to avoid blocking the async runtime
-        RecordingOutput::Single(data) => {
-            let api_key = api_key.clone();
-            tauri::async_runtime::spawn_blocking(move || {
-                transcribe_audio(&api_key, data)
-            })
-            .await
-            .map_err(|e| e.to_string())?
-            .map_err(|e| e.to_string())?
-        },
-        RecordingOutput::Chunked(chunks) => {
-            // parallel_transcribe is async, so we can await it directly
-            parallel_transcribe(&api_key, chunks, None)
-                .await
-                .map_err(|e| e.to_string())?
-        }
+        .is_some_and(AudioRecorder::is_warm)
+}
+
+/// Open or release the warm microphone to match settings. While recording, the
+/// change applies once the recording ends.
+pub fn apply_warm_mic(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    if matches!(
+        *state.state.lock().unwrap(),
+        RecordingState::Recording | RecordingState::Paused
+    ) {
+        return;
+    }
+    let (pre_roll, source) = {
+        let settings = state.settings.lock().unwrap();
+        (settings.pre_roll(), source::from_settings(&settings))
     };
 
-    // Copy to clipboard
-    copy_to_clipboard(&transcription).map_err(|e| e.to_string())?;
+    {
+        let mut recorder = state.recorder.lock().unwrap();
+        if let Some(mut old) = recorder.take() {
+            old.cool_down();
+        }
+        if let Some(pre_roll) = pre_roll {
+            let warm = source.and_then(|source| {
+                let mut warm = AudioRecorder::new()?;
+                warm.set_source(source);
+                warm.warm_up(pre_roll)?;
+                Ok(warm)
+            });
+            match warm {
+                Ok(warm) => *recorder = Some(warm),
+                Err(e) => eprintln!("Failed to open microphone for warm mic: {e}"),
+            }
+        }
+    }
 
-    // Reset state
+    let current = *state.state.lock().unwrap();
+    update_tray(app, current);
+}
+
+/// Switch the warm mic on or off from the tray
+fn toggle_warm_mic(app: &AppHandle) {
+    let state = app.state::<AppState>();
     {
-        *state.state.lock().unwrap() = RecordingState::Idle;
+        let mut settings = state.settings.lock().unwrap();
+        settings.pre_roll_ms = match settings.pre_roll() {
+            Some(_) => None,
+            None => Some(DEFAULT_PRE_ROLL_MS),
+        };
+        if let Err(e) = settings.save() {
+            eprintln!("Failed to save settings: {e}");
+        }
     }
-    update_tray(app, RecordingState::Idle);
+    apply_warm_mic(app);
+}
 
-    println!("Done: {}", &transcription[..transcription.len().min(50)]);
+/// List the connected microphones and rebuild the tray submenu
+pub fn refresh_device_menu(app: &AppHandle) {
+    let devices = whis_core::list_input_devices().unwrap_or_else(|e| {
+        eprintln!("Failed to list input devices: {e}");
+        Vec::new()
+    });
+    update_device_menu(app, &devices);
+}
+
+/// Rebuild the tray submenu from already listed microphones
+pub fn update_device_menu(app: &AppHandle, devices: &[InputDevice]) {
+    let state = app.state::<AppState>();
+    let Some(menu) = state.device_menu.lock().unwrap().clone() else {
+        return;
+    };
+    let selected = state.settings.lock().unwrap().input_device.clone();
+    if let Err(e) = fill_device_menu(app, &menu, devices, selected.as_deref()) {
+        eprintln!("Failed to update microphone menu: {e}");
+    }
+}
+
+fn fill_device_menu(
+    app: &AppHandle,
+    menu: &Submenu<tauri::Wry>,
+    devices: &[InputDevice],
+    selected: Option<&str>,
+) -> tauri::Result<()> {
+    for item in menu.items()? {
+        menu.remove(&item)?;
+    }
 
+    let default = CheckMenuItem::with_id(
+        app,
+        DEFAULT_DEVICE_ID,
+        "System Default",
+        true,
+        selected.is_none(),
+        None::<&str>,
+    )?;
+    menu.append(&default)?;
+
+    for device in devices {
+        let item = CheckMenuItem::with_id(
+            app,
+            format!("{DEVICE_ID_PREFIX}{}", device.name),
+            // A single & would mark a mnemonic
+            device.name.replace('&', "&&"),
+            true,
+            selected == Some(device.name.as_str()),
+            None::<&str>,
+        )?;
+        menu.append(&item)?;
+    }
+
+    // Keep an unplugged selection visible, recording falls back to the default
+    if let Some(name) = selected
+        && !devices.iter().any(|d| d.name == name)
+    {
+        let label = format!("{} (not connected)", name.replace('&', "&&"));
+        let item = CheckMenuItem::with_id(
+            app,
+            format!("{DEVICE_ID_PREFIX}{name}"),
+            label,
+            false,
+            true,
+            None::<&str>,
+        )?;
+        menu.append(&item)?;
+    }
     Ok(())
 }
 
+/// Error text with a hint on how to fix it from the desktop app
+fn user_message(err: &WhisError) -> String {
+    let hint = match err {
+        WhisError::RecordingSaved { source, dir } => {
+            return format!(
+                "{} (recording saved to {})",
+                user_message(source),
+                dir.display()
+            );
+        }
+        WhisError::NoInputDevice => "Connect a microphone and check your system sound settings.",
+        WhisError::EmptyRecording => "Check that the right microphone is selected and not muted.",
+        WhisError::FfmpegMissing => {
+            "Install FFmpeg (e.g. sudo apt install ffmpeg), or pick a built-in encoder in Settings > API Keys."
+        }
+        WhisError::MissingApiKey => "Add it in Settings > API Keys.",
+        WhisError::Authentication { .. } => "Check your key in Settings > API Keys.",
+        WhisError::RateLimited { .. } => "Wait a moment, or check your plan's usage limits.",
+        WhisError::QuotaExceeded { .. } => "Your account is out of credits; check its billing.",
+        WhisError::Timeout { .. } | WhisError::Network { .. } => {
+            "Check your internet connection or self-hosted server."
+        }
+        WhisError::ModelNotDownloaded(_) => "Download it with: whis model pull <name>",
+        WhisError::LocalWhisperUnavailable(_) => "Switch back to the API in Settings > API Keys.",
+        WhisError::ClipboardUnavailable(_) => "On Wayland, make sure wl-clipboard is installed.",
+        _ => return err.to_string(),
+    };
+    format!("{err}. {hint}")
+}
+
 fn update_tray(app: &AppHandle, new_state: RecordingState) {
     // Update menu item text using stored reference
     let app_state = app.state::<AppState>();
     if let Some(ref menu_item) = *app_state.record_menu_item.lock().unwrap() {
         let text = match new_state {
             RecordingState::Idle => "Start Recording",
-            RecordingState::Recording => "Stop Recording",
+            RecordingState::Recording | RecordingState::Paused => "Stop Recording",
             RecordingState::Transcribing => "Transcribing...",
         };
         let _ = menu_item.set_text(text);
         let _ = menu_item.set_enabled(new_state != RecordingState::Transcribing);
     }
+    let recording = matches!(new_state, RecordingState::Recording | RecordingState::Paused);
+    if let Some(ref menu_item) = *app_state.pause_menu_item.lock().unwrap() {
+        let text = match new_state {
+            RecordingState::Paused => "Resume Recording",
+            _ => "Pause Recording",
+        };
+        let _ = menu_item.set_text(text);
+        let _ = menu_item.set_enabled(recording);
+    }
+    if let Some(ref menu_item) = *app_state.discard_menu_item.lock().unwrap() {
+        let _ = menu_item.set_enabled(recording);
+    }
+    if let Some(ref menu_item) = *app_state.cancel_menu_item.lock().unwrap() {
+        let _ = menu_item.set_enabled(new_state == RecordingState::Transcribing);
+    }
+    let warm = is_warm(&app_state);
+    if let Some(ref menu_item) = *app_state.warm_menu_item.lock().unwrap() {
+        let _ = menu_item.set_checked(warm);
+    }
 
     if let Some(tray) = app.tray_by_id(TRAY_ID) {
-        // Update tooltip
+        // Update tooltip; say plainly when the microphone is open between recordings
         let tooltip = match new_state {
+            RecordingState::Idle if warm => "Whis - Warm mic on, microphone open - Click to record",
             RecordingState::Idle => "Whis - Click to record",
             RecordingState::Recording => "Whis - Recording... Click to stop",
+            RecordingState::Paused => "Whis - Paused - Click to stop",
             RecordingState::Transcribing => "Whis - Transcribing...",
         };
         let _ = tray.set_tooltip(Some(tooltip));
 
         // Set static icon based on state
         let icon = match new_state {
-            RecordingState::Idle => ICON_IDLE,
+            // Nothing is being captured while paused
+            RecordingState::Idle | RecordingState::Paused => ICON_IDLE,
             RecordingState::Recording => ICON_RECORDING,
             RecordingState::Transcribing => ICON_TRANSCRIBING,
         };